@@ -0,0 +1,49 @@
+// Compares a sequential initial index with a parallel one over a few thousand generated Python files.
+// The indexer logs every file to stdout, so results go to stderr: `cargo bench --bench initial_index > /dev/null`.
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use cortex::FileIndexer;
+
+const FILES: usize = 3000;
+const RUNS: usize = 3;
+
+fn write_project(root: &Path) {
+    for i in 0..FILES {
+        let path = root.join(format!("pkg{}/module_{}.py", i % 20, i));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut source = format!("import os\nfrom typing import List\n\nclass Model{i}:\n    limit = {i}\n\n");
+        for method in 0..10 {
+            source.push_str(&format!("    def method_{method}(self, values: List[int], scale: int = {method}) -> int:\n        total = 0\n        for value in values:\n            if value > self.limit:\n                total += value * scale\n        return total\n\n"));
+        }
+        std::fs::write(path, source).unwrap();
+    }
+}
+
+// The fastest of several runs, so a cold page cache on the first one doesn't skew the comparison.
+fn fastest_index(root: &Path, threads: usize) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let mut indexer = FileIndexer::from_root_project(root).unwrap().with_index_threads(threads);
+            let started = Instant::now();
+            indexer.initial_index().unwrap();
+            started.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(dir.path());
+
+    // At least two, so the worker path runs even on a single-core machine, where it can't win.
+    let parallel_threads = std::thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1).max(2);
+    let sequential = fastest_index(dir.path(), 1);
+    let parallel = fastest_index(dir.path(), parallel_threads);
+
+    eprintln!("initial index of {} files", FILES);
+    eprintln!("  sequential:           {:?}", sequential);
+    eprintln!("  parallel ({:>2} threads): {:?}", parallel_threads, parallel);
+    eprintln!("  speedup:              {:.2}x", sequential.as_secs_f64() / parallel.as_secs_f64());
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::parser::event::{FileEvents, ParseEvent};
+
+// Keyed by root-relative path, so indexes of different checkouts compare file by file; see `FileIndexer::snapshot`.
+pub type ProjectIndex = HashMap<PathBuf, FileEvents>;
+
+type ApiKey = (PathBuf, &'static str, String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiEntry {
+    pub file_path: PathBuf,
+    pub kind: &'static str,
+    pub name: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub file_path: PathBuf,
+    pub kind: &'static str,
+    pub name: String,
+    pub old_signature: String,
+    pub new_signature: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ApiDiff {
+    pub added: Vec<ApiEntry>,
+    pub removed: Vec<ApiEntry>,
+    pub changed: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    // Additions are backwards compatible; anything removed or re-signed can break callers.
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+}
+
+pub fn diff_public_api(old: &ProjectIndex, new: &ProjectIndex) -> ApiDiff {
+    let old_api = collect_public_api(old);
+    let mut new_api = collect_public_api(new);
+    let mut diff = ApiDiff::default();
+
+    for ((file_path, kind, name), old_signature) in old_api {
+        match new_api.remove(&(file_path.clone(), kind, name.clone())) {
+            None => diff.removed.push(ApiEntry { file_path, kind, name, signature: old_signature }),
+            Some(new_signature) if new_signature != old_signature => {
+                diff.changed.push(ApiChange { file_path, kind, name, old_signature, new_signature });
+            }
+            Some(_) => {}
+        }
+    }
+
+    diff.added = new_api.into_iter()
+        .map(|((file_path, kind, name), signature)| ApiEntry { file_path, kind, name, signature })
+        .collect();
+
+    diff
+}
+
+// Definitions sharing a name within one file (e.g. methods on different classes) are compared as one sorted group.
+fn collect_public_api(index: &ProjectIndex) -> BTreeMap<ApiKey, String> {
+    let mut grouped: BTreeMap<ApiKey, Vec<String>> = BTreeMap::new();
+
+    for (path, file_events) in index {
+        for event in file_events.public_api() {
+            let Some((kind, name, signature)) = describe(event) else {
+                continue;
+            };
+            grouped.entry((path.clone(), kind, name)).or_default().push(signature);
+        }
+    }
+
+    grouped.into_iter()
+        .map(|(key, mut signatures)| {
+            signatures.sort();
+            (key, signatures.join("; "))
+        })
+        .collect()
+}
+
+fn describe(event: &ParseEvent) -> Option<(&'static str, String, String)> {
+    match event {
+        ParseEvent::FunctionDefinition { name, parameters, return_type, .. } => {
+            let signature = match return_type {
+                Some(return_type) => format!("({}) -> {}", parameters.join(", "), return_type),
+                None => format!("({})", parameters.join(", ")),
+            };
+            Some(("function", name.clone(), signature))
+        }
+        ParseEvent::ClassDefinition { name, fields, .. } => {
+            Some(("class", name.clone(), fields.join(", ")))
+        }
+        ParseEvent::VariableDefinition { name, var_type, .. } => {
+            Some(("variable", name.clone(), var_type.clone().unwrap_or_default()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_watcher::FileIndexer;
+
+    fn snapshot_of(files: &[(&str, &str)]) -> (tempfile::TempDir, ProjectIndex) {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, source) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        let mut indexer = FileIndexer::from_root_project(dir.path()).unwrap();
+        indexer.initial_index().unwrap();
+        (dir, indexer.snapshot())
+    }
+
+    #[test]
+    fn removed_public_function_is_reported() {
+        let (_old_dir, old) = snapshot_of(&[("pkg/api.py", "def load(path):\n    pass\n\ndef save(path):\n    pass\n")]);
+        let (_new_dir, new) = snapshot_of(&[("pkg/api.py", "def load(path):\n    pass\n")]);
+
+        let diff = diff_public_api(&old, &new);
+
+        assert_eq!(diff.removed, vec![ApiEntry {
+            file_path: PathBuf::from("pkg/api.py"),
+            kind: "function",
+            name: "save".to_string(),
+            signature: "(path)".to_string(),
+        }]);
+        assert!(diff.added.is_empty());
+        assert!(diff.changed.is_empty());
+        assert!(diff.is_breaking());
+    }
+}
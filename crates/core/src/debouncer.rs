@@ -2,45 +2,63 @@ use std::collections::{HashMap, VecDeque};
 use std::path::{PathBuf, Path};
 use std::time::{Duration, Instant};
 
+pub trait Clock: Send {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
 pub struct Debouncer{
     debounce_duration: Duration,
     index_times: HashMap<PathBuf, Instant>, 
     order: VecDeque<(PathBuf, Instant)>,
+    clock: Box<dyn Clock>,
 }
 
 impl Debouncer {
     pub fn new(duration_secs: u64, duration_nanos: u32) -> Self {
+        Self::new_with_clock(duration_secs, duration_nanos, Box::new(SystemClock))
+    }
+
+    pub fn new_with_clock(duration_secs: u64, duration_nanos: u32, clock: Box<dyn Clock>) -> Self {
 
         Self {
             debounce_duration: Duration::new(duration_secs, duration_nanos), 
             index_times: HashMap::new(), 
             order: VecDeque::new(),
+            clock,
         }
     }
 
     fn cleanup(&mut self) {
-        let current_time = Instant::now();
+        let current_time = self.clock.now();
 
-        loop {
-            let should_remove = match self.order.front() {
-                Some((_, time)) => current_time.duration_since(*time) > self.debounce_duration,
-                None => break,
-            };
-            
-            if should_remove {
-                if let Some((path, _)) = self.order.pop_front() {
-                    self.index_times.remove(&path);
-                }
-            } else {
+        while let Some((_, time)) = self.order.front() {
+            if current_time.duration_since(*time) <= self.debounce_duration {
                 break;
             }
+            
+            if let Some((path, _)) = self.order.pop_front() {
+                self.index_times.remove(&path);
+            }
         }
     }
 
+    pub fn clear(&mut self) {
+        self.index_times.clear();
+        self.order.clear();
+    }
+
     pub fn should_index<P: AsRef<Path>>(&mut self, path: P) -> bool {
         self.cleanup();
 
-        let current_time = Instant::now();
+        let current_time = self.clock.now();
         match self.index_times.get(path.as_ref()) {
             Some(last_time) if current_time.duration_since(*last_time) < self.debounce_duration => {
                 false
@@ -57,7 +75,7 @@ impl Debouncer {
     pub fn time_left<P: AsRef<Path>>(&self, path: P) -> Duration {
     match self.index_times.get(path.as_ref()) {
         Some(last_time) => {
-            let elapsed = last_time.elapsed();
+            let elapsed = self.clock.now().duration_since(*last_time);
             if elapsed >= self.debounce_duration {
                 Duration::ZERO
             } else {
@@ -68,4 +86,81 @@ impl Debouncer {
     }
 }
 
-}
\ No newline at end of file
+}
+// A clock that only moves when told to, so debounce windows can be tested without sleeping.
+#[cfg(test)]
+#[derive(Clone)]
+pub struct MockClock {
+    now: std::sync::Arc<std::sync::Mutex<Instant>>,
+}
+
+#[cfg(test)]
+impl Default for MockClock {
+    fn default() -> Self {
+        Self { now: std::sync::Arc::new(std::sync::Mutex::new(Instant::now())) }
+    }
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(10);
+    const TICK: Duration = Duration::from_nanos(1);
+
+    #[test]
+    fn should_index_reopens_exactly_at_the_window_boundary() {
+        let clock = MockClock::default();
+        let mut debouncer = Debouncer::new_with_clock(10, 0, Box::new(clock.clone()));
+
+        assert!(debouncer.should_index("a.py"));
+
+        clock.advance(WINDOW - TICK);
+        assert!(!debouncer.should_index("a.py"));
+        assert_eq!(debouncer.time_left("a.py"), TICK);
+
+        clock.advance(TICK);
+        assert_eq!(debouncer.time_left("a.py"), Duration::ZERO);
+        assert!(debouncer.should_index("a.py"));
+    }
+
+    #[test]
+    fn should_index_after_the_window() {
+        let clock = MockClock::default();
+        let mut debouncer = Debouncer::new_with_clock(10, 0, Box::new(clock.clone()));
+
+        assert!(debouncer.should_index("a.py"));
+        clock.advance(WINDOW + TICK);
+
+        assert!(debouncer.should_index("a.py"));
+        assert!(!debouncer.should_index("a.py"));
+    }
+
+    #[test]
+    fn paths_are_debounced_independently() {
+        let clock = MockClock::default();
+        let mut debouncer = Debouncer::new_with_clock(10, 0, Box::new(clock.clone()));
+
+        assert!(debouncer.should_index("a.py"));
+        clock.advance(WINDOW / 2);
+        assert!(debouncer.should_index("b.py"));
+
+        clock.advance(WINDOW / 2);
+        assert!(debouncer.should_index("a.py"));
+        assert!(!debouncer.should_index("b.py"));
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum CortexError {
+    RootNotFound { path: PathBuf },
+    IndexFailed { path: PathBuf, reason: String },
+    UnsupportedFile { path: PathBuf },
+    ParseFailed { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for CortexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CortexError::RootNotFound { path } => write!(f, "Root path does not exist: {}", path.display()),
+            CortexError::IndexFailed { path, reason } => write!(f, "Failed to index {}: {}", path.display(), reason),
+            CortexError::UnsupportedFile { path } => write!(f, "No parser registered for {}", path.display()),
+            CortexError::ParseFailed { path, reason } => write!(f, "Failed to parse {}: {}", path.display(), reason),
+        }
+    }
+}
+
+impl std::error::Error for CortexError {}
@@ -1,21 +1,98 @@
 use std::collections::HashSet;
 use std::path::Path;
 
+// Interpreters named in a shebang line, mapped to the extension their scripts would normally have.
+const SHEBANG_INTERPRETERS: [(&str, &str); 9] = [
+    ("python", "py"), ("node", "js"), ("bash", "sh"), ("sh", "sh"), ("zsh", "sh"),
+    ("ruby", "rb"), ("perl", "pl"), ("lua", "lua"), ("deno", "ts"),
+];
+
+// The last two suffixes of a file name, e.g. `d.ts` for `foo.d.ts` or `tar.gz` for `backup.tar.gz`.
+// Path::extension only sees the final one. A leading dot (hidden file) isn't a separator.
+pub fn compound_extension(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?.trim_start_matches('.');
+    let mut segments = name.rsplit('.');
+    let last = segments.next()?;
+    let second = segments.next()?;
+    segments.next()?;
+
+    (!last.is_empty() && !second.is_empty()).then(|| format!("{}.{}", second, last))
+}
+
 pub struct ExtensionFilter {
     supported_extensions: HashSet<String>,
+    supported_filenames: HashSet<String>,
 }
 
 impl ExtensionFilter {
     pub fn new(extensions: Vec<&str>) -> Self {
         let set = extensions.into_iter().map(|s| s.to_string()).collect();
-        Self { supported_extensions: set }
+        Self { supported_extensions: set, supported_filenames: HashSet::new() }
+    }
+
+    // A leading dot is accepted, so `.rs` and `rs` are the same extension.
+    pub fn add_extension(&mut self, ext: &str) {
+        self.supported_extensions.insert(ext.trim_start_matches('.').to_string());
+    }
+
+    pub fn set_extensions(&mut self, extensions: Vec<&str>) {
+        self.supported_extensions.clear();
+        self.add_extensions(extensions);
+    }
+
+    pub fn add_extensions(&mut self, extensions: Vec<&str>) {
+        for ext in extensions {
+            self.add_extension(ext);
+        }
+    }
+
+    // Returns whether the extension was supported before.
+    pub fn remove_extension(&mut self, ext: &str) -> bool {
+        self.supported_extensions.remove(ext.trim_start_matches('.'))
+    }
+
+    pub fn supported_extensions(&self) -> impl Iterator<Item = &str> {
+        self.supported_extensions.iter().map(String::as_str)
+    }
+
+    // Exact file names, e.g. `Dockerfile` or `Makefile`, that are indexed despite having no extension.
+    pub fn add_filenames(&mut self, filenames: Vec<&str>) {
+        self.supported_filenames.extend(filenames.into_iter().map(|s| s.to_string()));
     }
 
     pub fn is_supported<P: AsRef<Path>>(&self, path: P) -> bool {
+        if compound_extension(path.as_ref()).is_some_and(|ext| self.supported_extensions.contains(&ext)) {
+            return true;
+        }
+
         path.as_ref()
             .extension()
             .and_then(|ext| ext.to_str())
             .map(|ext| self.supported_extensions.contains(ext))
             .unwrap_or(false)
     }
-}
+
+    // Fallback for files the extension check rejects: the name allowlist, then the shebang interpreter.
+    pub fn is_supported_by_content<P: AsRef<Path>>(&self, path: P, first_line: &str) -> bool {
+        let allowlisted = path.as_ref()
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| self.supported_filenames.contains(name));
+
+        allowlisted || Self::shebang_extension(first_line).is_some_and(|ext| self.supported_extensions.contains(ext))
+    }
+
+    // `#!/usr/bin/env python3` and `#!/usr/bin/python3 -u` both name `python`; version suffixes are ignored.
+    pub fn shebang_extension(first_line: &str) -> Option<&'static str> {
+        let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+        let mut interpreter = words.next()?.rsplit('/').next()?;
+        if interpreter == "env" {
+            interpreter = words.find(|word| !word.starts_with('-'))?;
+        }
+
+        let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        SHEBANG_INTERPRETERS.iter()
+            .find(|(name, _)| *name == interpreter)
+            .map(|(_, ext)| *ext)
+    }
+}
\ No newline at end of file
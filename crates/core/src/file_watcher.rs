@@ -1,45 +1,365 @@
-use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::{HashMap, HashSet};
+use encoding_rs::Encoding;
+use notify::event::{CreateKind, DataChange, ModifyKind, RemoveKind, RenameMode};
+use notify_debouncer_full::{new_debouncer_opt, DebounceEventResult, FileIdMap};
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
-use std::sync::mpsc::{channel, Receiver};
-use std::vec;
+use std::time::{Duration, Instant, SystemTime};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
 
+use crate::api_diff::ProjectIndex;
 use crate::parser::registry::LanguageParserRegistry;
-use crate::parser::event::{FileEvents, ParseEvent};
+use crate::parser::r#trait::LanguageParser;
+use crate::parser::event::{CommentType, FileEvents, FileSummary, ParseEvent};
 use crate::debouncer::Debouncer;
+use crate::error::CortexError;
 use crate::extension_filter::ExtensionFilter;
 use crate::ignore_matcher::IgnoreMatcher;
 use crate::index_decider:: IndexDecider;
+use crate::manifest::ManifestHints;
+use crate::parse_pool::{ParseOutcome, ParsePool};
+use crate::rate_limiter::RateLimiter;
+use crate::source_reader::{SourceReader, TreeCache};
+use crate::symbol_index::SymbolIndex;
+
+// How long the `From` half of a rename waits for its `To`/`Both` before it is taken as a move out of the tree.
+const RENAME_PAIR_WINDOW: Duration = Duration::from_millis(200);
+
+// The `From` half of a rename (inotify reports `From`, `To`, then `Both`), held until the `Both` that
+// pairs it arrives, so the old path isn't deleted before its events can move.
+struct PendingRename {
+    from: PathBuf,
+    to: Option<PathBuf>,
+    since: Instant,
+}
+
+const DEFAULT_EXTENSIONS: [&str; 38] = [
+    "sh", "c", "cpp", "cc", "cxx", "h", "hpp", "css", "d", "ex", "exs", "erl", "hrl", "go", 
+    "hs", "html", "htm", "java", "js", "mjs", "cjs", "json", "lua", "md", "markdown", "pl", "pm", "py", 
+    "rb", "rs", "toml", "ts", "tsx", "jsx", "vim", "yaml", "yml", "ipynb"
+    ];
+
+type EventReceiver = Receiver<Result<Event, notify::Error>>;
+
+type EventSender = Sender<Result<Event, notify::Error>>;
+
+// Only held so the underlying watcher (native, polling, or debounced) stays alive while the event loop runs.
+type WatcherHandle = Box<dyn Any>;
+
+type FileIndexedCallback = Box<dyn FnMut(&Path, &FileEvents)>;
+
+type ParseObserver = Box<dyn FnMut(&FileEvents)>;
+
+const PARSE_RESULT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const POLL_FALLBACK_INTERVAL: Duration = Duration::from_secs(2);
+
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+const MODIFY_COALESCE_WINDOW: Duration = Duration::from_millis(50);
+
+// How long a stop request can go unnoticed while the event loop is idle.
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// One file read and parsed by an initial-index worker, waiting to be recorded on the indexer's thread.
+struct InitialParse {
+    path: PathBuf,
+    modified_before_read: Option<SystemTime>,
+    over_line_limit: bool,
+    outcome: Result<Option<FileEvents>, String>,
+}
+
+// Ends a running start_watching from another thread, e.g. a Ctrl-C handler.
+#[derive(Clone)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatcherBackend {
+    // Native OS notifications, downgrading to polling if they can't be registered.
+    Auto,
+    Native,
+    Poll,
+    // Slow polling for network mounts, where native notifications are dropped or delayed.
+    Network,
+}
 
 pub struct FileIndexer {
-    root_path: PathBuf,
+    root_paths: Vec<PathBuf>,
     indexed_files: HashSet<PathBuf>,
     index_decider: IndexDecider,
-    parser_registry: LanguageParserRegistry,
+    source_reader: SourceReader,
     all_file_events: HashMap<PathBuf, FileEvents>,
+    summary_only: bool,
+    file_summaries: HashMap<PathBuf, FileSummary>,
+    file_metadata: HashMap<PathBuf, HashMap<String, String>>,
+    reparse_on_rename: bool,
+    pending_renames: HashMap<usize, PendingRename>,
+    notify_debounce: Option<Duration>,
+    modify_coalescer: Debouncer,
+    watcher_backend: WatcherBackend,
+    poll_interval: Duration,
+    watched_file: Option<PathBuf>,
+    walk_errors: Vec<(PathBuf, std::io::Error)>,
+    indexed_mtimes: HashMap<PathBuf, SystemTime>,
+    rate_limiter: Option<RateLimiter>,
+    pending_parses: VecDeque<PathBuf>,
+    parse_threads: Option<usize>,
+    index_threads: usize,
+    parse_pool: Option<ParsePool>,
+    on_file_indexed: Option<FileIndexedCallback>,
+    observers: Vec<ParseObserver>,
+    initial_indexing: bool,
+    reconcile_interval: Option<Duration>,
+    next_reconcile: Option<Instant>,
+    stop_requested: Arc<AtomicBool>,
 }
 
 impl FileIndexer {
-    pub fn from_root_project<P: AsRef<Path>>(root: P) -> Self {
-        let file_extensions = vec![
-            "sh", "c", "cpp", "cc", "cxx", "h", "hpp", "css", "d", "ex", "exs", "erl", "hrl", "go", 
-            "hs", "html", "htm", "java", "js", "mjs", "cjs", "json", "lua", "md", "markdown", "pl", "pm", "py", 
-            "rb", "rs", "toml", "ts", "tsx", "jsx", "vim", "yaml", "yml"
-            ];
-
-        let matcher = IgnoreMatcher::from_root_project(&root, Vec::new()); 
-        let filter = ExtensionFilter::new(file_extensions); 
+    pub fn from_root_project<P: AsRef<Path>>(root: P) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_roots(vec![root.as_ref().to_path_buf()])
+    }
+
+    // Every root is walked and watched, but they share one index and one event loop.
+    pub fn from_roots(roots: Vec<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        if roots.is_empty() {
+            return Err("at least one root is required".into());
+        }
+
+        let matchers = roots.iter()
+            .map(|root| IgnoreMatcher::from_root_project(root, Vec::new()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let filter = ExtensionFilter::new(DEFAULT_EXTENSIONS.to_vec()); 
         let debouncer = Debouncer::new(10, 0); 
-        let decider = IndexDecider::new(matcher, filter, debouncer);
+        let decider = IndexDecider::new(matchers, filter, debouncer);
 
-        Self {
-            root_path: root.as_ref().to_path_buf(),
+        Ok(Self {
+            root_paths: roots,
             indexed_files: HashSet::new(),
             index_decider: decider,
-            parser_registry: LanguageParserRegistry::new(),
+            source_reader: SourceReader::new(LanguageParserRegistry::new()),
             all_file_events: HashMap::new(),
+            summary_only: false,
+            file_summaries: HashMap::new(),
+            file_metadata: HashMap::new(),
+            reparse_on_rename: true,
+            pending_renames: HashMap::new(),
+            notify_debounce: None,
+            modify_coalescer: Debouncer::new(MODIFY_COALESCE_WINDOW.as_secs(), MODIFY_COALESCE_WINDOW.subsec_nanos()),
+            watcher_backend: WatcherBackend::Auto,
+            poll_interval: POLL_FALLBACK_INTERVAL,
+            watched_file: None,
+            walk_errors: Vec::new(),
+            indexed_mtimes: HashMap::new(),
+            rate_limiter: None,
+            pending_parses: VecDeque::new(),
+            parse_threads: None,
+            index_threads: thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1),
+            parse_pool: None,
+            on_file_indexed: None,
+            observers: Vec::new(),
+            initial_indexing: false,
+            reconcile_interval: None,
+            next_reconcile: None,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn with_ignore_files(mut self, ignore_files: Vec<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        for matcher in self.index_decider.ignore_matchers_mut() {
+            let user_ignores: Vec<&str> = matcher.user_ignores().iter().map(String::as_str).collect();
+            *matcher = IgnoreMatcher::with_ignore_files(matcher.root(), ignore_files.clone(), user_ignores)?;
+        }
+        Ok(self)
+    }
+
+    // Replaces the default extension list rather than adding to it.
+    pub fn with_extensions(mut self, extensions: Vec<&str>) -> Self {
+        self.index_decider.extension_filter_mut().set_extensions(extensions);
+        self
+    }
+
+    pub fn with_ignore_patterns(mut self, patterns: Vec<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        for matcher in self.index_decider.ignore_matchers_mut() {
+            for pattern in &patterns {
+                matcher.add_pattern(pattern)?;
+            }
+        }
+        Ok(self)
+    }
+
+    // Indexes files with these exact names (e.g. `Dockerfile`) even though they have no extension.
+    pub fn with_extensionless_files(mut self, filenames: Vec<&str>) -> Self {
+        self.index_decider.extension_filter_mut().add_filenames(filenames);
+        self
+    }
+
+    // Each root is checked for its own manifests; the extensions they imply apply to every root.
+    pub fn with_manifest_detection(mut self) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut extensions: Vec<&str> = Vec::new();
+
+        for matcher in self.index_decider.ignore_matchers_mut() {
+            let hints = ManifestHints::detect(matcher.root());
+            if hints.is_empty() {
+                continue;
+            }
+
+            extensions.extend(hints.extensions);
+            let ignore_files: Vec<&str> = matcher.ignore_files().iter().map(String::as_str).collect();
+            let mut user_ignores: Vec<&str> = matcher.user_ignores().iter().map(String::as_str).collect();
+            user_ignores.extend(hints.ignores);
+            *matcher = IgnoreMatcher::with_ignore_files(matcher.root(), ignore_files, user_ignores)?;
+        }
+
+        self.index_decider.extension_filter_mut().add_extensions(extensions);
+        Ok(self)
+    }
+
+    pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+        self.source_reader.default_encoding = encoding;
+        self
+    }
+
+    pub fn with_extension_encoding(mut self, extension: &str, encoding: &'static Encoding) -> Self {
+        self.source_reader.extension_encodings.insert(extension.to_string(), encoding);
+        self
+    }
+
+    pub fn with_mmap_threshold(mut self, threshold_bytes: u64) -> Self {
+        self.source_reader.mmap_threshold = Some(threshold_bytes);
+        self
+    }
+
+    // Files longer than this are still tracked, so deletes and renames are seen, but never parsed.
+    // Independent of the mmap threshold, which only changes how a file is read.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.source_reader.max_lines = Some(max_lines);
+        self
+    }
+
+    // Keeps each file's last syntax tree so a modification only re-parses the part that changed,
+    // at the cost of holding every indexed file's tree and source in memory.
+    pub fn with_incremental_reparse(mut self) -> Self {
+        self.source_reader.tree_cache = Some(TreeCache::default());
+        self
+    }
+
+    // Files with these extensions are parsed from a memory map in chunks instead of being read into memory.
+    pub fn with_streaming_extensions(mut self, extensions: Vec<&str>) -> Self {
+        self.source_reader.streaming_extensions.extend(extensions.into_iter().map(|ext| ext.to_string()));
+        self
+    }
+
+    pub fn with_reparse_on_rename(mut self, reparse_on_rename: bool) -> Self {
+        self.reparse_on_rename = reparse_on_rename;
+        self
+    }
+
+    // Two debounce layers: the notify debouncer coalesces raw OS bursts (e.g. an editor's
+    // truncate + write + chmod) into one delivered event per path, while the IndexDecider's
+    // Debouncer still decides whether that delivered event is allowed to trigger a reparse.
+    pub fn with_notify_debounce(mut self, timeout: Duration) -> Self {
+        self.notify_debounce = Some(timeout);
+        self
+    }
+
+    // Some backends report one save as several modify kinds (e.g. Data followed by Any); modify events
+    // for the same path inside `window` count once. A zero window turns coalescing off.
+    pub fn with_modify_coalescing(mut self, window: Duration) -> Self {
+        self.modify_coalescer = Debouncer::new(window.as_secs(), window.subsec_nanos());
+        self
+    }
+
+    pub fn with_watcher_backend(mut self, backend: WatcherBackend) -> Self {
+        self.watcher_backend = backend;
+        self
+    }
+
+    // Polls instead of using native notifications; also the interval Auto falls back to.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.watcher_backend = WatcherBackend::Poll;
+        self.poll_interval = interval;
+        self
+    }
+
+    // Polls slowly and, every `sweep_interval`, compares each indexed file's mtime with the one
+    // recorded at index time and the files on disk with the index, handling whatever was created,
+    // modified or removed without an event reaching us as if the event had arrived.
+    pub fn with_network_mode(mut self, sweep_interval: Duration) -> Self {
+        self.watcher_backend = WatcherBackend::Network;
+        self.reconcile_interval = Some(sweep_interval);
+        self
+    }
+
+    pub fn with_snippets(self, enabled: bool) -> Self {
+        self.configure_parsers(|registry| registry.set_record_snippets(enabled))
+    }
+
+    pub fn with_comment_marker(self, language: &str, marker: &str, comment_type: CommentType) -> Self {
+        self.configure_parsers(|registry| registry.set_comment_marker(language, marker, comment_type))
+    }
+
+    // Only parses triggered by watcher events are throttled; the initial walk is bounded by the tree size.
+    pub fn with_max_parses_per_second(mut self, max_per_second: usize) -> Self {
+        self.rate_limiter = Some(RateLimiter::per_second(max_per_second));
+        self
+    }
+
+    pub fn with_source_order(self, enabled: bool) -> Self {
+        self.configure_parsers(|registry| registry.set_normalize_order(enabled))
+    }
+
+    pub fn with_max_function_lines(self, max_lines: usize) -> Self {
+        self.configure_parsers(|registry| registry.set_max_function_lines(max_lines))
+    }
+
+    // Keeps only per-file counts instead of full events, for callers that never query symbols.
+    pub fn with_summary_only(mut self) -> Self {
+        self.summary_only = true;
+        self
+    }
+
+    // Watcher-triggered parses run on this many worker threads instead of blocking the event loop.
+    pub fn with_parse_threads(mut self, threads: usize) -> Self {
+        self.parse_threads = Some(threads);
+        self
+    }
+
+    // Called with each file's events as soon as that file is parsed during the initial index, so
+    // consumers can show the outline while the rest of the project is still being walked.
+    pub fn with_on_file_indexed<F: FnMut(&Path, &FileEvents) + 'static>(mut self, callback: F) -> Self {
+        self.on_file_indexed = Some(Box::new(callback));
+        self
+    }
+
+    // Threads that read and parse files during the initial index; defaults to the available parallelism.
+    pub fn with_index_threads(mut self, threads: usize) -> Self {
+        self.index_threads = threads.max(1);
+        self
+    }
+
+    pub fn with_parser(self, parser: Box<dyn LanguageParser>) -> Self {
+        self.configure_parsers(|registry| registry.register_parser(parser))
+    }
+
+    // Builders consume the indexer before it starts any parse worker, so the registry is never shared
+    // here; if it somehow were, the change is reported and skipped rather than panicking.
+    fn configure_parsers(mut self, configure: impl FnOnce(&mut LanguageParserRegistry)) -> Self {
+        match self.source_reader.registry_mut() {
+            Some(registry) => configure(registry),
+            None => eprintln!("Parser registry is in use by parse workers; configuration not applied"),
         }
+        self
     }
 
     fn index_file(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
@@ -50,208 +370,1770 @@ impl FileIndexer {
             return Ok(());
         }
 
-        let content = std::fs::read_to_string(path)?;
+        if self.skip_over_line_limit(path)? {
+            return Ok(());
+        }
 
-        if let Some(file_events) = self.parser_registry.parse_file(path, &content)? {
-            for func in file_events.functions(){ //throwaway
-                println!("Functions Definition: {:?}, ", func);
-            }
-            println!();
-            //SQL queries
-            
-            return Err("File extension not supported".into());
-        } else {
+        let parsed = self.source_reader.parse_source(path)?;
+        self.record_parse(path, parsed)
+    }
+
+    // Results are keyed by canonical path, the same key indexed_files and the rename handling use.
+    fn record_parse(&mut self, path: &Path, parsed: Option<FileEvents>) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(file_events) = parsed else {
             println!("  - No parser available for this file type");
+            return Ok(());
+        };
+
+        let canonical_path = canonicalize_or_fallback(path);
+        println!("  - {} events", file_events.event_count());
+
+        if self.initial_indexing && let Some(callback) = &mut self.on_file_indexed {
+            callback(&canonical_path, &file_events);
+        }
+        for observer in &mut self.observers {
+            observer(&file_events);
         }
-        Ok(())
-    }
 
-    fn create_file(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        println!("File created: {}", path.display());
-        
-        if self.index_decider.should_index(path) {
-            self.index_file(path)?;
+        if self.summary_only {
+            self.file_summaries.insert(canonical_path, FileSummary::from(&file_events));
+        } else {
+            self.all_file_events.insert(canonical_path, file_events);
         }
-        
         Ok(())
     }
 
-    fn delete_file(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        println!("File deleted: {}", path.display());
-        
-        // Later: remove logic here
-        
-        Ok(())
+    // Observers see every successful parse, from the initial index and from watcher-triggered reparses
+    // alike, e.g. to keep a symbol database or a language server in sync without polling.
+    pub fn add_observer(&mut self, observer: ParseObserver) {
+        self.observers.push(observer);
     }
 
-    pub fn start_watching(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn clear(&mut self) {
+        self.file_metadata.clear();
+        self.indexed_files.clear();
+        self.all_file_events.clear();
+        self.file_summaries.clear();
+        self.indexed_mtimes.clear();
+        self.pending_parses.clear();
+        self.pending_renames.clear();
+        self.source_reader.clear_trees();
+        self.walk_errors.clear();
+        self.index_decider.clear_debouncer();
+    }
 
-        let root = &self.root_path.clone();
-        
-        self.initial_index(root)?;
+    // Always None in summary-only mode, which keeps just the FileSummary.
+    pub fn file_events(&self, path: &Path) -> Option<&FileEvents> {
+        self.all_file_events.get(&canonicalize_or_fallback(path))
+    }
 
-        let (_watcher, rx) = self.setup_watcher()?;
-        
-        self.print_status();
-        self.program_loop(&rx);
+    pub fn file_summaries(&self) -> &HashMap<PathBuf, FileSummary> {
+        &self.file_summaries
+    }
 
-        Ok(())
+    // A copy of the index keyed by root-relative path, so snapshots of two checkouts line up for
+    // `diff_public_api`. Same-named files under different roots share a key; empty in summary-only mode.
+    pub fn snapshot(&self) -> ProjectIndex {
+        self.all_file_events.iter()
+            .map(|(path, file_events)| (self.relative_path(path), file_events.clone()))
+            .collect()
     }
 
-    fn initial_index(&mut self, root: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Starting initial indexing of: {}", self.root_path.display());
-        
-        self.walk_directory(root)?;
-        
-        println!("Initial indexing complete. Indexed {} files.", self.indexed_files.len());
-        Ok(())
+    // Index keys are canonical, so the roots are too before being stripped.
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        self.root_paths.iter()
+            .find_map(|root| path.strip_prefix(canonicalize_or_fallback(root)).ok())
+            .unwrap_or(path)
+            .to_path_buf()
     }
 
-    fn walk_directory(&mut self, dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        if !dir.is_dir() {
-            return Ok(());
-        }
+    // Arbitrary key/value data owned by the embedding application; it follows renames and is dropped on delete.
+    pub fn set_metadata(&mut self, path: &Path, key: &str, value: &str) {
+        self.file_metadata.entry(canonicalize_or_fallback(path))
+            .or_default()
+            .insert(key.to_string(), value.to_string());
+    }
 
-        let entries = std::fs::read_dir(dir)?;
-        
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_file() {
-                if self.index_decider.should_index(&path) {
-                    match self.index_file(&path) {
-                        Ok(()) => {
-                            let canonized_path = path.canonicalize()?;
-                            self.indexed_files.insert(canonized_path.clone());
-                            println!("Successfully indexed and tracked: {}", canonized_path.display());
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to index {}: {}", path.display(), e);
-                        }
+    pub fn get_metadata(&self, path: &Path, key: &str) -> Option<&str> {
+        self.file_metadata.get(&canonicalize_or_fallback(path))?
+            .get(key)
+            .map(String::as_str)
+    }
+
+    pub fn languages_present(&self) -> Vec<String> {
+        let languages: BTreeSet<&str> = self.all_file_events.values()
+            .map(|file_events| file_events.language_name())
+            .collect();
+        languages.into_iter().map(|language| language.to_string()).collect()
+    }
+
+    pub fn symbol_index(&self) -> SymbolIndex {
+        SymbolIndex::from_files(self.all_file_events.values())
+    }
+
+    pub fn export_ctags<W: Write>(&self, mut w: W) -> std::io::Result<()> {
+        let mut tags: Vec<(String, String, usize, char)> = Vec::new();
+
+        for (path, file_events) in &self.all_file_events {
+            let file = self.relative_path(path).display().to_string();
+
+            for event in &file_events.events {
+                match event {
+                    ParseEvent::FunctionDefinition { name, start_line, .. } => {
+                        tags.push((name.clone(), file.clone(), *start_line, 'f'));
+                    }
+                    ParseEvent::ClassDefinition { name, start_line, .. } => {
+                        tags.push((name.clone(), file.clone(), *start_line, 'c'));
                     }
+                    ParseEvent::MacroDefinition { name, start_line, .. } => {
+                        tags.push((name.clone(), file.clone(), *start_line, 'd'));
+                    }
+                    _ => {}
                 }
-            } else if path.is_dir() {
-                self.walk_directory(&path)?;
             }
         }
-              
-        Ok(())   
-    }
 
-    fn setup_watcher(&self) -> Result<(RecommendedWatcher, Receiver<Result<Event, notify::Error>>), Box<dyn std::error::Error>> {
-        let (tx, rx) = channel();
-        
-        let mut watcher = RecommendedWatcher::new(
-            tx,
-            Config::default().with_poll_interval(Duration::from_millis(100))
-        )?;
+        tags.sort();
 
-        println!("Setting up recursive watch on: {}", self.root_path.display());
-        watcher.watch(&self.root_path, RecursiveMode::Recursive)?;
+        writeln!(w, "!_TAG_FILE_FORMAT\t2\t/extended format/")?;
+        writeln!(w, "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/")?;
+        for (name, file, line, kind) in tags {
+            writeln!(w, "{}\t{}\t{};\"\t{}", name, file, line, kind)?;
+        }
 
-        Ok((watcher, rx))
+        Ok(())
     }
 
-    fn program_loop(&mut self, rx: &Receiver<Result<Event, notify::Error>>){
-        loop {
-            match rx.recv() {
-                Ok(Ok(event)) => {
-                    if let Err(e) = self.handle_event(event) {
-                        eprintln!("Error handling event: {}", e);
-                    }
-                }
-                Ok(Err(e)) => eprintln!("Watch error: {:?}", e),
-                Err(e) => {
-                    eprintln!("Channel error: {:?}", e);
-                    break;
-                }
+    fn request_index(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(rate_limiter) = &mut self.rate_limiter
+            && !rate_limiter.try_acquire() {
+            if !self.pending_parses.iter().any(|pending| pending == path) {
+                println!("  - Parse rate limit reached, queueing {}", path.display());
+                self.pending_parses.push_back(path.to_path_buf());
             }
+            return Ok(());
         }
+
+        self.dispatch_index(path)
     }
-    
-    fn print_status(&self){
-        let file_count = self.indexed_files.len();
-        
-        if file_count > 0 {
-            println!("File watcher started. Monitoring {} specific files.", file_count);
+
+    // The mtime is recorded like track_indexed does, so the reconciliation sweep knows this version is indexed.
+    fn dispatch_index(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let modified_before_read = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+        if self.parse_pool.is_some() && self.skip_over_line_limit(path)? {
+            self.record_mtime(path, modified_before_read);
+            return Ok(());
+        }
+
+        match &mut self.parse_pool {
+            Some(pool) => pool.submit(path.to_path_buf()),
+            None => self.index_file(path)?,
         }
+        self.record_mtime(path, modified_before_read);
+        Ok(())
     }
 
-    fn handle_event(&mut self, event: Event) -> Result<(), Box<dyn std::error::Error>> {
-        match event.kind {
-                        EventKind::Modify(notify::event::ModifyKind::Name(_)) => {
-                self.handle_file_rename(event);
-            }
-            EventKind::Modify(notify::event::ModifyKind::Data(_))
-            |EventKind::Modify(notify::event::ModifyKind::Other)
-            |EventKind::Modify(notify::event::ModifyKind::Any)  => {
-                self.handle_file_modification(event);
+    fn record_mtime(&mut self, path: &Path, modified: Option<SystemTime>) {
+        if let Some(modified) = modified {
+            self.indexed_mtimes.insert(canonicalize_or_fallback(path), modified);
+        }
+    }
+
+    // A file that grew past the limit also loses the events recorded while it was still short enough.
+    fn skip_over_line_limit(&mut self, path: &Path) -> Result<bool, std::io::Error> {
+        if !self.source_reader.exceeds_line_limit(path)? {
+            return Ok(false);
+        }
+
+        println!("  - Over the line limit, tracking without parsing");
+        let canonical_path = canonicalize_or_fallback(path);
+        self.all_file_events.remove(&canonical_path);
+        self.file_summaries.remove(&canonical_path);
+        Ok(true)
+    }
+
+    fn collect_parse_results(&mut self) {
+        let mut finished: Vec<ParseOutcome> = Vec::new();
+        if let Some(pool) = &mut self.parse_pool {
+            while let Some(outcome) = pool.try_recv() {
+                finished.push(outcome);
             }
-    
-            EventKind::Create(_) => {
-                self.handle_file_creation(event);
+        }
+
+        for (path, outcome) in finished {
+            let result = outcome.map_err(|e| e.into()).and_then(|parsed| self.record_parse(&path, parsed));
+            if let Err(e) = result {
+                eprintln!("Failed to index {}: {}", path.display(), e);
             }
-            EventKind::Remove(_) => {
-                self.handle_file_deletion(event);
+        }
+    }
+
+    fn process_pending_parses(&mut self) {
+        while !self.pending_parses.is_empty() {
+            if let Some(rate_limiter) = &mut self.rate_limiter
+                && !rate_limiter.try_acquire() {
+                break;
             }
 
-            _ => {
-                println!("Unhandled event type {:?}", event.kind);
+            if let Some(path) = self.pending_parses.pop_front()
+                && let Err(e) = self.dispatch_index(&path) {
+                eprintln!("Failed to index {}: {}", path.display(), e);
             }
         }
-        Ok(())
-    }
-    
-    fn handle_file_modification(&mut self, event: Event){
-        for path in event.paths {
-            let canonicolized_path = &path.canonicalize().unwrap();
-            if self.indexed_files.contains(canonicolized_path) {
-                if self.index_decider.should_index(canonicolized_path){
-                    if let Err(e) = self.index_file(&canonicolized_path) {
-                        eprintln!("Failed to index {}: {}", path.display(), e);
-                    }
-                } else {
-                    println!("Debouncer time left {:?}", self.index_decider.debounce_duration_left(canonicolized_path))
-                }            
-            }      
-        }   
     }
-    
-    fn handle_file_creation(&mut self, event: Event){
-        for path in event.paths {
-            if path.is_file() && self.index_decider.should_index(&path) {
-                if let Err(e) = self.create_file(&path) {
-                     eprintln!("Failed to handle creation of {}: {}", path.display(), e);
-                }
-            }
+
+    fn pending_parse_delay(&mut self) -> Option<Duration> {
+        let result_poll = self.parse_pool.as_ref()
+            .filter(|pool| pool.has_in_flight())
+            .map(|_| PARSE_RESULT_POLL_INTERVAL);
+
+        if self.pending_parses.is_empty() {
+            return result_poll;
+        }
+
+        let rate_delay = self.rate_limiter.as_mut().map(|rate_limiter| rate_limiter.time_until_available());
+        match (rate_delay, result_poll) {
+            (Some(rate_delay), Some(result_poll)) => Some(rate_delay.min(result_poll)),
+            (rate_delay, result_poll) => rate_delay.or(result_poll),
         }
     }
 
-    fn handle_file_deletion(&mut self, event: Event){
-        for path in event.paths {
-            if let Err(e) = self.delete_file(&path) {
-                    eprintln!("Failed to handle deletion of {}: {}", path.display(), e);
-            }
+    // Callers have already asked the decider; asking again would be refused by its debouncer.
+    // Tracked straight away so later modifications of the new file are picked up.
+    fn create_file(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        println!("File created: {}", path.display());
+        
+        self.request_index(path)?;
+        self.indexed_files.insert(canonicalize_or_fallback(path));
+        
+        Ok(())
+    }
+
+    // Files already indexed that the new pattern excludes are dropped from the index.
+    pub fn add_ignore_pattern(&mut self, pattern: &str) -> Result<(), Box<dyn std::error::Error>> {
+        for matcher in self.index_decider.ignore_matchers_mut() {
+            matcher.add_pattern(pattern)?;
         }
+
+        self.remove_ignored_files();
+        Ok(())
     }
 
-    fn handle_file_rename(&mut self, event: Event){
-        for path in event.paths {
-            if path.exists() {
-                if self.index_decider.should_index(&path) {
-                    if let Err(e) = self.create_file(&path) {
-                        eprintln!("Failed to handle rename/move to {}: {}", path.display(), e);
-                    }
-                }
-            } else {
-                if self.index_decider.should_index(&path) {
-                    if let Err(e) = self.delete_file(&path) {
-                        eprintln!("Failed to handle rename/move from {}: {}", path.display(), e);
-                    }
-                }
-            }
+    fn remove_ignored_files(&mut self) {
+        let now_ignored: Vec<PathBuf> = self.indexed_files.iter()
+            .filter(|path| self.index_decider.is_ignored(path))
+            .cloned()
+            .collect();
+        for path in now_ignored {
+            println!("{} is now ignored, removing it from the index", path.display());
+            self.remove_from_index(&path);
         }
     }
 
+    // Returns whether the path was part of the index.
+    fn delete_file(&mut self, path: &Path) -> bool {
+        println!("File deleted: {}", path.display());
+        self.remove_from_index(path)
+    }
+
+    fn remove_from_index(&mut self, path: &Path) -> bool {
+        let canonical_path = canonicalize_or_fallback(path);
+
+        self.file_metadata.remove(&canonical_path);
+        self.indexed_mtimes.remove(&canonical_path);
+        self.source_reader.forget_tree(&canonical_path);
+        let had_events = self.all_file_events.remove(&canonical_path).is_some();
+        let had_summary = self.file_summaries.remove(&canonical_path).is_some();
+        self.indexed_files.remove(&canonical_path) || had_events || had_summary
+    }
+
+    pub fn start_watching(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let (watcher, rx) = self.prepare_watching()?;
+        self.program_loop(&rx);
+
+        // Dropping the pool waits for parses already in flight; their results are discarded.
+        drop(watcher);
+        self.parse_pool = None;
+        self.stop_requested.store(false, Ordering::SeqCst);
+        println!("File watcher stopped.");
+
+        Ok(())
+    }
+
+    // Everything start_watching does before the event loop takes over.
+    fn prepare_watching(&mut self) -> Result<(WatcherHandle, EventReceiver), Box<dyn std::error::Error>> {
+        if let Some(missing) = self.root_paths.iter().find(|root| !root.exists()) {
+            return Err(CortexError::RootNotFound { path: missing.clone() }.into());
+        }
+
+        // A file can only be watched on its own; watched_file filters every event down to it.
+        match self.root_paths.as_slice() {
+            [root] if root.is_file() => self.watched_file = Some(root.canonicalize()?),
+            roots => if let Some(file) = roots.iter().find(|root| root.is_file()) {
+                return Err(format!("{} is a file; only a single root can be a file", file.display()).into());
+            },
+        }
+
+        self.initial_index()?;
+
+        if let Some(threads) = self.parse_threads {
+            self.parse_pool = Some(ParsePool::new(threads, self.source_reader.clone()));
+        }
+
+        let (watcher, rx) = self.setup_watcher()?;
+        
+        self.print_status();
+        Ok((watcher, rx))
+    }
+
+    // Makes start_watching return after the event it is currently handling. The index is kept, so
+    // watching can be started again later.
+    pub fn stop(&self) {
+        self.stop_handle().stop();
+    }
+
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle(Arc::clone(&self.stop_requested))
+    }
+
+    // Indexes every root once without watching, e.g. to take a `snapshot`; `start_watching` calls it itself.
+    pub fn initial_index(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let roots: Vec<String> = self.root_paths.iter().map(|root| root.display().to_string()).collect();
+        println!("Starting initial indexing of: {}", roots.join(", "));
+        self.walk_errors.clear();
+        
+        self.initial_indexing = true;
+        let result = self.index_initial_files();
+        self.initial_indexing = false;
+        result?;
+        
+        println!("Initial indexing complete. Indexed {} files.", self.indexed_files.len());
+        if !self.walk_errors.is_empty() {
+            println!("Skipped {} unreadable paths during the walk.", self.walk_errors.len());
+        }
+        Ok(())
+    }
+
+    fn index_initial_files(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.watched_file.clone() {
+            Some(file) => self.index_and_track(&file)?,
+            None => {
+                let mut candidates: Vec<PathBuf> = Vec::new();
+                for root in self.root_paths.clone() {
+                    self.walk_directory(&root, &mut candidates);
+                }
+                // A root nested inside another is walked twice; parse each file once.
+                candidates.sort();
+                candidates.dedup();
+                self.index_in_parallel(candidates)?;
+            }
+        }
+        self.reconcile_modified_files()
+    }
+
+    // Workers only read and parse; every result is recorded here, on the indexer's thread, as soon as
+    // it arrives, so the index itself needs no locking and on_file_indexed still fires per file.
+    fn index_in_parallel(&mut self, paths: Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let threads = self.index_threads.min(paths.len());
+        if threads <= 1 {
+            for path in &paths {
+                self.track_indexed(path)?;
+            }
+            return Ok(());
+        }
+
+        // A clone shares the parser registry; it lets the workers read while the index is being written.
+        let reader = self.source_reader.clone();
+        let next_path = AtomicUsize::new(0);
+        let (tx, rx) = channel::<InitialParse>();
+
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                let (tx, reader, paths, next_path) = (tx.clone(), &reader, &paths, &next_path);
+                scope.spawn(move || {
+                    while let Some(path) = paths.get(next_path.fetch_add(1, Ordering::Relaxed)) {
+                        if tx.send(Self::read_and_parse(reader, path)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+
+            // Dropping the receiver on error makes the workers' next send fail, which stops them.
+            for parsed in rx {
+                self.record_initial_parse(parsed)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn read_and_parse(reader: &SourceReader, path: &Path) -> InitialParse {
+        // Taken before reading so a write landing mid-parse shows up as a newer mtime afterwards.
+        let modified_before_read = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+        let (over_line_limit, outcome) = match reader.exceeds_line_limit(path) {
+            Ok(true) => (true, Ok(None)),
+            Ok(false) => (false, reader.parse_source(path).map_err(|e| e.to_string())),
+            Err(e) => (false, Err(e.to_string())),
+        };
+
+        InitialParse { path: path.to_path_buf(), modified_before_read, over_line_limit, outcome }
+    }
+
+    fn record_initial_parse(&mut self, parsed: InitialParse) -> Result<(), std::io::Error> {
+        println!("Indexing file: {}", parsed.path.display());
+
+        if parsed.over_line_limit {
+            println!("  - Over the line limit, tracking without parsing");
+        } else {
+            let result = parsed.outcome.map_err(|e| e.into()).and_then(|events| self.record_parse(&parsed.path, events));
+            if let Err(e) = result {
+                eprintln!("Failed to index {}: {}", parsed.path.display(), e);
+                return Ok(());
+            }
+        }
+
+        self.mark_indexed(&parsed.path, parsed.modified_before_read)
+    }
+
+    fn reconcile_modified_files(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let changed: Vec<PathBuf> = self.indexed_mtimes.iter()
+            .filter(|(path, recorded)| {
+                std::fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|current| current != **recorded)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in changed {
+            println!("{} changed since it was indexed, re-indexing", path.display());
+            self.track_indexed(&path)?;
+        }
+
+        Ok(())
+    }
+
+    // Turns what a dropped event would have reported into that event and hands it to handle_event,
+    // so missed changes go through the same debouncing, rate limiting and parse pool as seen ones.
+    fn reconcile_missed_events(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let modified: Vec<PathBuf> = self.indexed_mtimes.iter()
+            .filter(|(path, recorded)| {
+                std::fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .is_ok_and(|current| current != **recorded)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        let removed: Vec<PathBuf> = self.indexed_files.iter()
+            .filter(|path| !path.exists())
+            .cloned()
+            .collect();
+
+        let created: Vec<PathBuf> = self.unindexed_files();
+
+        let missed = [
+            (EventKind::Remove(RemoveKind::File), removed),
+            (EventKind::Create(CreateKind::File), created),
+            (EventKind::Modify(ModifyKind::Data(DataChange::Content)), modified),
+        ];
+        for (kind, paths) in missed {
+            for path in paths {
+                println!("Reconciliation found a missed {:?} for {}", kind, path.display());
+                self.handle_event(Event::new(kind).add_path(path))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Files on disk the rules admit but the index doesn't have, found without asking the debouncer.
+    fn unindexed_files(&mut self) -> Vec<PathBuf> {
+        let mut on_disk: Vec<PathBuf> = Vec::new();
+        match self.watched_file.clone() {
+            Some(file) => on_disk.extend((file.is_file() && self.index_decider.is_indexable(&file)).then_some(file)),
+            None => {
+                self.walk_errors.clear();
+                for root in self.root_paths.clone() {
+                    self.walk_files(&root, |decider, path| decider.is_indexable(path), &mut on_disk);
+                }
+            }
+        }
+
+        on_disk.into_iter()
+            .filter(|path| !self.indexed_files.contains(&canonicalize_or_fallback(path)))
+            .collect()
+    }
+
+    // The first call only schedules the sweep; initial_index has just reconciled everything.
+    fn reconcile_if_due(&mut self) {
+        let Some(interval) = self.reconcile_interval else {
+            return;
+        };
+
+        let now = Instant::now();
+        if self.next_reconcile.is_none_or(|next| now < next) {
+            self.next_reconcile.get_or_insert(now + interval);
+            return;
+        }
+
+        self.next_reconcile = Some(now + interval);
+        if let Err(e) = self.reconcile_missed_events() {
+            eprintln!("Reconciliation sweep failed: {}", e);
+        }
+    }
+
+    fn reconcile_delay(&self) -> Option<Duration> {
+        self.next_reconcile.map(|next| next.saturating_duration_since(Instant::now()))
+    }
+
+    // Collects the files to index rather than indexing them, so they can be parsed in parallel.
+    fn walk_directory(&mut self, dir: &Path, candidates: &mut Vec<PathBuf>) {
+        self.walk_files(dir, |decider, path| decider.should_index(path), candidates);
+    }
+
+    // `select` decides which files are collected; ignored directories are never entered.
+    fn walk_files(&mut self, dir: &Path, select: fn(&mut IndexDecider, &Path) -> bool, candidates: &mut Vec<PathBuf>) {
+        if !dir.is_dir() {
+            return;
+        }
+
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.record_walk_error(dir, e);
+                return;
+            }
+        };
+        
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    self.record_walk_error(dir, e);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            
+            if path.is_file() {
+                if select(&mut self.index_decider, &path) {
+                    candidates.push(path);
+                }
+            } else if path.is_dir() && !self.index_decider.is_ignored_dir(&path) {
+                self.walk_files(&path, select, candidates);
+            }
+        }
+    }
+
+    fn record_walk_error(&mut self, path: &Path, error: std::io::Error) {
+        eprintln!("Skipping {}: {}", path.display(), error);
+        self.walk_errors.push((path.to_path_buf(), error));
+    }
+
+    pub fn walk_errors(&self) -> &[(PathBuf, std::io::Error)] {
+        &self.walk_errors
+    }
+
+    fn index_and_track(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if self.index_decider.should_index(path) {
+            self.track_indexed(path)?;
+        }
+
+        Ok(())
+    }
+
+    fn track_indexed(&mut self, path: &Path) -> Result<(), std::io::Error> {
+        // Taken before reading so a write landing mid-parse shows up as a newer mtime afterwards.
+        let modified_before_read = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+        match self.index_file(path) {
+            Ok(()) => self.mark_indexed(path, modified_before_read)?,
+            Err(e) => {
+                eprintln!("Failed to index {}: {}", path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn mark_indexed(&mut self, path: &Path, modified_before_read: Option<SystemTime>) -> Result<(), std::io::Error> {
+        let canonized_path = path.canonicalize()?;
+        if let Some(modified) = modified_before_read {
+            self.indexed_mtimes.insert(canonized_path.clone(), modified);
+        }
+        self.indexed_files.insert(canonized_path.clone());
+        println!("Successfully indexed and tracked: {}", canonized_path.display());
+        Ok(())
+    }
+
+    pub fn index_paths(&mut self, paths: &[PathBuf]) -> Result<(), CortexError> {
+        for path in paths {
+            if !path.is_file() || !self.index_decider.should_index(path) {
+                println!("Skipping {}: not a file or filtered out", path.display());
+                continue;
+            }
+
+            self.track_indexed(path)
+                .map_err(|e| CortexError::IndexFailed { path: path.clone(), reason: e.to_string() })?;
+        }
+
+        Ok(())
+    }
+
+    // A single watched file is observed through its parent directory; handle_event filters out the siblings.
+    fn watch_targets(&self) -> Vec<(&Path, RecursiveMode)> {
+        match self.watched_file.as_deref().and_then(|file| file.parent()) {
+            Some(parent) => vec![(parent, RecursiveMode::NonRecursive)],
+            None => self.root_paths.iter().map(|root| (root.as_path(), RecursiveMode::Recursive)).collect(),
+        }
+    }
+
+    fn setup_watcher(&self) -> Result<(WatcherHandle, EventReceiver), Box<dyn std::error::Error>> {
+        self.setup_watcher_with::<RecommendedWatcher>()
+    }
+
+    // `N` is the native backend, a parameter only so tests can stand in one that fails to start.
+    fn setup_watcher_with<N: Watcher + 'static>(&self) -> Result<(WatcherHandle, EventReceiver), Box<dyn std::error::Error>> {
+        let (tx, rx) = channel();
+
+        for (watch_path, recursive_mode) in self.watch_targets() {
+            println!("Setting up {:?} watch on: {}", recursive_mode, watch_path.display());
+        }
+
+        let native_config = Config::default().with_poll_interval(Duration::from_millis(100));
+        let poll_config = Config::default().with_poll_interval(self.poll_interval);
+
+        let handle = match self.watcher_backend {
+            WatcherBackend::Native => self.start_watcher::<N>(tx, native_config)?,
+            WatcherBackend::Poll => self.start_watcher::<PollWatcher>(tx, poll_config)?,
+            WatcherBackend::Network => self.start_watcher::<PollWatcher>(tx, Config::default().with_poll_interval(NETWORK_POLL_INTERVAL))?,
+            WatcherBackend::Auto => match self.start_watcher::<N>(tx.clone(), native_config) {
+                Ok(handle) => handle,
+                Err(error) => {
+                    println!("Native watcher failed ({}), falling back to polling every {:?}", error, self.poll_interval);
+                    self.start_watcher::<PollWatcher>(tx, poll_config)?
+                }
+            },
+        };
+
+        Ok((handle, rx))
+    }
+
+    fn start_watcher<W: Watcher + 'static>(&self, tx: EventSender, config: Config) -> Result<WatcherHandle, Box<dyn std::error::Error>> {
+        let watch_targets = self.watch_targets();
+
+        match self.notify_debounce {
+            Some(timeout) => {
+                let mut debouncer = new_debouncer_opt::<_, W, FileIdMap>(timeout, None, move |result: DebounceEventResult| {
+                    match result {
+                        Ok(events) => {
+                            for debounced in events {
+                                let _ = tx.send(Ok(debounced.event));
+                            }
+                        }
+                        Err(errors) => {
+                            for error in errors {
+                                let _ = tx.send(Err(error));
+                            }
+                        }
+                    }
+                }, FileIdMap::new(), config)?;
+
+                for (watch_path, recursive_mode) in watch_targets {
+                    debouncer.watcher().watch(watch_path, recursive_mode)?;
+                    debouncer.cache().add_root(watch_path, recursive_mode);
+                }
+                Ok(Box::new(debouncer))
+            }
+            None => {
+                let mut watcher = W::new(tx, config)?;
+                for (watch_path, recursive_mode) in watch_targets {
+                    watcher.watch(watch_path, recursive_mode)?;
+                }
+                Ok(Box::new(watcher))
+            }
+        }
+    }
+
+    fn program_loop(&mut self, rx: &EventReceiver){
+        while !self.stop_requested.load(Ordering::SeqCst) {
+            self.collect_parse_results();
+            self.process_pending_parses();
+            self.flush_unpaired_renames();
+            self.reconcile_if_due();
+
+            let delay = [self.pending_parse_delay(), self.rename_pair_delay(), self.reconcile_delay()].into_iter()
+                .flatten()
+                .fold(STOP_POLL_INTERVAL, Duration::min);
+            let received = rx.recv_timeout(delay);
+
+            match received {
+                Ok(Ok(event)) => {
+                    if let Err(e) = self.handle_event(event) {
+                        eprintln!("Error handling event: {}", e);
+                    }
+                }
+                Ok(Err(e)) => eprintln!("Watch error: {:?}", e),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(e) => {
+                    eprintln!("Channel error: {:?}", e);
+                    break;
+                }
+            }
+        }
+    }
+    
+    fn print_status(&self){
+        let file_count = self.indexed_files.len();
+        
+        if file_count > 0 {
+            println!("File watcher started. Monitoring {} specific files.", file_count);
+        }
+    }
+
+    fn handle_event(&mut self, mut event: Event) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(watched_file) = &self.watched_file {
+            event.paths.retain(|path| canonicalize_or_fallback(path) == *watched_file);
+            if event.paths.is_empty() {
+                return Ok(());
+            }
+        }
+
+        // Ignore files decide which paths the decider admits; they are never indexed themselves.
+        if !matches!(event.kind, EventKind::Access(_)) && event.paths.iter().any(|path| self.is_ignore_file(path)) {
+            event.paths.retain(|path| !self.is_ignore_file(path));
+            self.reload_ignore_rules()?;
+            if event.paths.is_empty() {
+                return Ok(());
+            }
+        }
+
+        match event.kind {
+                        EventKind::Modify(notify::event::ModifyKind::Name(_)) => {
+                self.handle_file_rename(event);
+            }
+            EventKind::Modify(notify::event::ModifyKind::Data(_))
+            |EventKind::Modify(notify::event::ModifyKind::Other)
+            |EventKind::Modify(notify::event::ModifyKind::Any)  => {
+                self.handle_file_modification(event);
+            }
+    
+            EventKind::Create(_) => {
+                self.handle_file_creation(event);
+            }
+            EventKind::Remove(_) => {
+                self.handle_file_deletion(event);
+            }
+
+            _ => {
+                println!("Unhandled event type {:?}", event.kind);
+            }
+        }
+        Ok(())
+    }
+    
+    // Ignore files count at any depth, since nested ones apply to their own subtree.
+    fn is_ignore_file(&self, path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                self.index_decider.ignore_matcher_for(path)
+                    .is_some_and(|matcher| matcher.ignore_files().iter().any(|file_name| file_name == name))
+            })
+    }
+
+    // Only files whose status the new rules flip are touched: newly ignored ones leave the index and
+    // newly admitted ones are indexed. Saving an ignore file without changing it does nothing.
+    fn reload_ignore_rules(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut changed = false;
+        for matcher in self.index_decider.ignore_matchers_mut() {
+            changed |= matcher.reload()?;
+        }
+        if !changed {
+            println!("Ignore file touched, rules unchanged");
+            return Ok(());
+        }
+
+        println!("Ignore rules changed, re-checking which files belong in the index");
+        self.remove_ignored_files();
+        for path in self.unindexed_files() {
+            println!("{} is no longer ignored, indexing it", path.display());
+            self.request_index(&path)?;
+            self.indexed_files.insert(canonicalize_or_fallback(&path));
+        }
+        Ok(())
+    }
+
+    fn handle_file_modification(&mut self, event: Event){
+        for path in event.paths {
+            if !self.modify_coalescer.should_index(canonicalize_or_fallback(&path)) {
+                continue;
+            }
+
+            // Atomic saves (write a temp file, rename it over the original) can remove the path
+            // between the event firing and it being handled here.
+            let canonicolized_path = &match path.canonicalize() {
+                Ok(canonical) => canonical,
+                Err(_) if !path.exists() => {
+                    println!("Skipping modification of {}: file no longer exists", path.display());
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Skipping modification of {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if self.indexed_files.contains(canonicolized_path) {
+                if self.index_decider.should_index(canonicolized_path){
+                    if let Err(e) = self.request_index(canonicolized_path) {
+                        eprintln!("Failed to index {}: {}", path.display(), e);
+                    }
+                } else {
+                    println!("Debouncer time left {:?}", self.index_decider.debounce_duration_left(canonicolized_path))
+                }            
+            }      
+        }   
+    }
+    
+    fn handle_file_creation(&mut self, event: Event){
+        for path in event.paths {
+            if path.is_file() && self.index_decider.should_index(&path)
+                && let Err(e) = self.create_file(&path) {
+                eprintln!("Failed to handle creation of {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn handle_file_deletion(&mut self, event: Event){
+        for path in event.paths {
+            self.delete_file(&path);
+        }
+    }
+
+    fn handle_file_rename(&mut self, event: Event){
+        let tracker = event.attrs.tracker();
+
+        match (event.kind, tracker, event.paths.as_slice()) {
+            (EventKind::Modify(ModifyKind::Name(RenameMode::From)), Some(tracker), [from]) => {
+                self.pending_renames.insert(tracker, PendingRename { from: from.clone(), to: None, since: Instant::now() });
+            }
+            (EventKind::Modify(ModifyKind::Name(RenameMode::To)), Some(tracker), [to]) if self.pending_renames.contains_key(&tracker) => {
+                if let Some(pending) = self.pending_renames.get_mut(&tracker) {
+                    pending.to = Some(to.clone());
+                }
+            }
+            (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), _, [from, to]) => {
+                if let Some(tracker) = tracker {
+                    self.pending_renames.remove(&tracker);
+                }
+                self.move_file(from, to);
+            }
+            _ => {
+                for path in &event.paths {
+                    self.handle_renamed_path(path);
+                }
+            }
+        }
+    }
+
+    // A `From` whose pair never came was a move out of the tree; a `To` without a `Both` is indexed as it stands.
+    fn flush_unpaired_renames(&mut self) {
+        let expired: Vec<usize> = self.pending_renames.iter()
+            .filter(|(_, pending)| pending.since.elapsed() >= RENAME_PAIR_WINDOW)
+            .map(|(tracker, _)| *tracker)
+            .collect();
+
+        for tracker in expired {
+            if let Some(pending) = self.pending_renames.remove(&tracker) {
+                self.handle_renamed_path(&pending.from);
+                if let Some(to) = pending.to {
+                    self.handle_renamed_path(&to);
+                }
+            }
+        }
+    }
+
+    fn rename_pair_delay(&self) -> Option<Duration> {
+        self.pending_renames.values()
+            .map(|pending| RENAME_PAIR_WINDOW.saturating_sub(pending.since.elapsed()))
+            .min()
+    }
+
+    fn move_file(&mut self, from: &Path, to: &Path) {
+        if let Some(metadata) = self.file_metadata.remove(&canonicalize_or_fallback(from)) {
+            self.file_metadata.insert(canonicalize_or_fallback(to), metadata);
+        }
+
+        if !self.reparse_on_rename && self.move_index_entry(from, to) {
+            return;
+        }
+
+        self.handle_renamed_path(from);
+        self.handle_renamed_path(to);
+    }
+
+    fn handle_renamed_path(&mut self, path: &Path) {
+        if path.exists() {
+            if self.index_decider.should_index(path)
+                && let Err(e) = self.create_file(path) {
+                eprintln!("Failed to handle rename/move to {}: {}", path.display(), e);
+            }
+        } else {
+            // Not gated on should_index: the debouncer would refuse a path indexed moments ago.
+            self.delete_file(path);
+        }
+    }
+
+    // Moves everything keyed by the old path, so the file is indexed under its new name exactly as before.
+    fn move_index_entry(&mut self, from: &Path, to: &Path) -> bool {
+        let old_path = canonicalize_or_fallback(from);
+        let Ok(new_path) = to.canonicalize() else {
+            return false;
+        };
+
+        if !self.indexed_files.contains(&old_path) || !self.index_decider.should_index(&new_path) {
+            return false;
+        }
+
+        println!("File moved: {} -> {}", old_path.display(), new_path.display());
+        if let Some(mut file_events) = self.all_file_events.remove(&old_path) {
+            file_events.file_path = new_path.clone();
+            self.all_file_events.insert(new_path.clone(), file_events);
+        }
+        if let Some(mut summary) = self.file_summaries.remove(&old_path) {
+            summary.path = new_path.clone();
+            self.file_summaries.insert(new_path.clone(), summary);
+        }
+        if let Some(modified) = self.indexed_mtimes.remove(&old_path) {
+            self.indexed_mtimes.insert(new_path.clone(), modified);
+        }
+        self.source_reader.move_tree(&old_path, &new_path);
+        self.indexed_files.remove(&old_path);
+        self.indexed_files.insert(new_path);
+        true
+    }
+
+}
+
+// A deleted or renamed-away file can no longer be canonicalized directly, so resolve its parent instead.
+fn canonicalize_or_fallback(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+
+    match (path.parent().and_then(|parent| parent.canonicalize().ok()), path.file_name()) {
+        (Some(parent), Some(file_name)) => parent.join(file_name),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::event::Language;
+
+    // Canonicalized so paths built from it match the canonical keys the index uses.
+    fn project(files: &[(&str, &str)]) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        for (name, content) in files {
+            let path = root.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        (dir, root)
+    }
+
+    #[test]
+    fn ctags_line_for_a_known_function() {
+        let (_dir, root) = project(&[("pkg/util.py", "import os\n\ndef helper():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+
+        let mut tags: Vec<u8> = Vec::new();
+        indexer.export_ctags(&mut tags).unwrap();
+        let tags = String::from_utf8(tags).unwrap();
+
+        assert!(tags.starts_with("!_TAG_FILE_FORMAT"));
+        assert!(tags.lines().any(|line| line == "helper\tpkg/util.py\t3;\"\tf"), "{}", tags);
+    }
+
+    #[test]
+    fn ctags_line_for_a_macro_definition() {
+        let (_dir, root) = project(&[("src/lib.rs", "macro_rules! foo {\n    () => {};\n}\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        let path = root.join("src/lib.rs");
+        let mut file_events = FileEvents::new(path.clone(), Language::Rust, std::time::SystemTime::now());
+        file_events.add_event(ParseEvent::MacroDefinition { name: "foo".to_string(), start_line: 1, start_column: 1, end_line: 3, snippet: None });
+        indexer.all_file_events.insert(path, file_events);
+
+        let mut tags: Vec<u8> = Vec::new();
+        indexer.export_ctags(&mut tags).unwrap();
+        let tags = String::from_utf8(tags).unwrap();
+
+        assert!(tags.lines().any(|line| line == "foo\tsrc/lib.rs\t1;\"\td"), "{}", tags);
+    }
+
+    fn parse_counter(indexer: &mut FileIndexer) -> std::rc::Rc<std::cell::Cell<usize>> {
+        let parses = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = std::rc::Rc::clone(&parses);
+        indexer.add_observer(Box::new(move |_| counter.set(counter.get() + 1)));
+        parses
+    }
+
+    fn rename_event(from: &Path, to: &Path) -> Event {
+        Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(from.to_path_buf())
+            .add_path(to.to_path_buf())
+    }
+
+    fn handle_events_until_quiet(indexer: &mut FileIndexer, rx: &EventReceiver, quiet: Duration) {
+        while let Ok(received) = rx.recv_timeout(quiet) {
+            if let Ok(event) = received {
+                indexer.handle_event(event).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn move_policy_transfers_events_without_reparsing() {
+        let (_dir, root) = project(&[("old.py", "def moved():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_reparse_on_rename(false);
+        indexer.initial_index().unwrap();
+        let parses = parse_counter(&mut indexer);
+
+        let (from, to) = (root.join("old.py"), root.join("new.py"));
+        std::fs::rename(&from, &to).unwrap();
+        indexer.handle_event(rename_event(&from, &to)).unwrap();
+
+        assert_eq!(parses.get(), 0);
+        assert!(indexer.file_events(&from).is_none());
+        let file_events = indexer.file_events(&to).unwrap();
+        assert_eq!(file_events.file_path, to);
+        assert_eq!(file_events.functions().count(), 1);
+        assert!(indexer.indexed_files.contains(&to) && !indexer.indexed_files.contains(&from));
+    }
+
+    #[test]
+    fn reparse_policy_parses_the_renamed_file() {
+        let (_dir, root) = project(&[("old.py", "def moved():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        let parses = parse_counter(&mut indexer);
+
+        let (from, to) = (root.join("old.py"), root.join("new.py"));
+        std::fs::rename(&from, &to).unwrap();
+        indexer.handle_event(rename_event(&from, &to)).unwrap();
+
+        assert_eq!(parses.get(), 1);
+        assert!(indexer.file_events(&from).is_none());
+        assert!(indexer.file_events(&to).is_some());
+        assert!(indexer.indexed_files.contains(&to) && !indexer.indexed_files.contains(&from));
+    }
+
+    #[test]
+    fn move_policy_follows_a_rename_seen_by_the_watcher() {
+        let (_dir, root) = project(&[("old.py", "def moved():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_reparse_on_rename(false);
+        indexer.initial_index().unwrap();
+        let parses = parse_counter(&mut indexer);
+        let (_watcher, rx) = indexer.setup_watcher().unwrap();
+
+        let (from, to) = (root.join("old.py"), root.join("new.py"));
+        std::fs::rename(&from, &to).unwrap();
+        handle_events_until_quiet(&mut indexer, &rx, Duration::from_millis(500));
+        indexer.flush_unpaired_renames();
+
+        assert_eq!(parses.get(), 0);
+        assert!(indexer.file_events(&from).is_none());
+        assert_eq!(indexer.file_events(&to).unwrap().file_path, to);
+        assert!(indexer.indexed_files.contains(&to) && !indexer.indexed_files.contains(&from));
+    }
+
+    #[test]
+    fn move_policy_carries_the_summary_mtime_and_cached_tree() {
+        let (_dir, root) = project(&[("old.py", "def moved():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_reparse_on_rename(false)
+            .with_summary_only()
+            .with_incremental_reparse();
+        indexer.initial_index().unwrap();
+
+        let (from, to) = (root.join("old.py"), root.join("new.py"));
+        std::fs::rename(&from, &to).unwrap();
+        indexer.handle_event(rename_event(&from, &to)).unwrap();
+
+        assert_eq!(indexer.file_summaries().keys().collect::<Vec<_>>(), vec![&to]);
+        assert_eq!(indexer.file_summaries()[&to].path, to);
+        assert!(indexer.indexed_mtimes.contains_key(&to) && !indexer.indexed_mtimes.contains_key(&from));
+        let cache = indexer.source_reader.tree_cache.as_ref().unwrap().lock().unwrap();
+        assert!(cache.contains_key(&to) && !cache.contains_key(&from));
+    }
+
+    #[test]
+    fn unpaired_rename_away_is_a_delete_once_the_pair_window_passes() {
+        let (_dir, root) = project(&[("gone.py", "x = 1\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        let from = root.join("gone.py");
+
+        std::fs::remove_file(&from).unwrap();
+        let moved_away = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From))).add_path(from.clone()).set_tracker(7);
+        indexer.handle_event(moved_away).unwrap();
+        assert!(indexer.indexed_files.contains(&from));
+        assert_eq!(indexer.rename_pair_delay().map(|delay| delay <= RENAME_PAIR_WINDOW), Some(true));
+
+        std::thread::sleep(RENAME_PAIR_WINDOW);
+        indexer.flush_unpaired_renames();
+        assert!(!indexer.indexed_files.contains(&from));
+        assert!(indexer.file_events(&from).is_none());
+        assert_eq!(indexer.rename_pair_delay(), None);
+    }
+
+    // Everything the watcher delivers for `path` until it has been quiet for `quiet`.
+    fn drain_events_for(rx: &EventReceiver, path: &Path, quiet: Duration) -> Vec<EventKind> {
+        let mut kinds = Vec::new();
+        while let Ok(received) = rx.recv_timeout(quiet) {
+            if let Ok(event) = received
+                && event.paths.iter().any(|event_path| event_path == path) {
+                kinds.push(event.kind);
+            }
+        }
+        kinds
+    }
+
+    #[test]
+    fn notify_debouncer_delivers_one_event_for_a_burst_of_writes() {
+        let (_dir, root) = project(&[("burst.py", "x = 0\n")]);
+        let path = root.join("burst.py");
+        let indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_notify_debounce(Duration::from_millis(400));
+        let (_watcher, rx) = indexer.setup_watcher().unwrap();
+
+        // The debouncer flushes on ticks of a quarter of its timeout, starting when it's created. A
+        // burst landing right on a tick boundary can be split in two, so start half a tick in.
+        std::thread::sleep(Duration::from_millis(50));
+        for i in 0..10 {
+            std::fs::write(&path, format!("x = {}\n", i)).unwrap();
+        }
+        let kinds = drain_events_for(&rx, &path, Duration::from_secs(1));
+
+        let modifications = kinds.iter().filter(|kind| matches!(kind, EventKind::Modify(_))).count();
+        assert_eq!(modifications, 1, "{:?}", kinds);
+    }
+
+    #[test]
+    fn without_notify_debouncer_each_write_is_delivered() {
+        let (_dir, root) = project(&[("burst.py", "x = 0\n")]);
+        let path = root.join("burst.py");
+        let indexer = FileIndexer::from_root_project(&root).unwrap();
+        let (_watcher, rx) = indexer.setup_watcher().unwrap();
+
+        for i in 0..10 {
+            std::fs::write(&path, format!("x = {}\n", i)).unwrap();
+        }
+        let kinds = drain_events_for(&rx, &path, Duration::from_millis(500));
+
+        let modifications = kinds.iter().filter(|kind| matches!(kind, EventKind::Modify(_))).count();
+        assert!(modifications > 1, "{:?}", kinds);
+    }
+
+    #[test]
+    fn missing_root_is_reported_as_root_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("does-not-exist");
+        let mut indexer = FileIndexer::from_root_project(&missing).unwrap();
+
+        let error = indexer.start_watching().unwrap_err();
+
+        match error.downcast_ref::<CortexError>() {
+            Some(CortexError::RootNotFound { path }) => assert_eq!(path, &missing),
+            _ => panic!("expected RootNotFound, got {}", error),
+        }
+    }
+
+    fn parsed_files(indexer: &mut FileIndexer) -> std::rc::Rc<std::cell::RefCell<Vec<PathBuf>>> {
+        let parsed = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = std::rc::Rc::clone(&parsed);
+        indexer.add_observer(Box::new(move |file_events| recorder.borrow_mut().push(file_events.file_path.clone())));
+        parsed
+    }
+
+    #[test]
+    fn single_file_root_indexes_only_that_file() {
+        let (_dir, root) = project(&[("watched.py", "def a():\n    pass\n"), ("sibling.py", "def b():\n    pass\n")]);
+        let (watched, sibling) = (root.join("watched.py"), root.join("sibling.py"));
+        let mut indexer = FileIndexer::from_root_project(&watched).unwrap();
+        let parsed = parsed_files(&mut indexer);
+
+        let (_watcher, rx) = indexer.prepare_watching().unwrap();
+        assert_eq!(*parsed.borrow(), vec![watched.clone()]);
+
+        // The initial index just marked the file in the decider's debouncer.
+        indexer.index_decider.clear_debouncer();
+        std::fs::write(&watched, "def a():\n    pass\n\ndef c():\n    pass\n").unwrap();
+        std::fs::write(&sibling, "def b2():\n    pass\n").unwrap();
+        handle_events_until_quiet(&mut indexer, &rx, Duration::from_millis(500));
+
+        assert_eq!(*parsed.borrow(), vec![watched.clone(), watched.clone()]);
+        assert_eq!(indexer.file_events(&watched).unwrap().functions().count(), 2);
+        assert!(indexer.file_events(&sibling).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_directory_is_reported_and_skipped() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, root) = project(&[("app.py", "def a():\n    pass\n"), ("locked/hidden.py", "def b():\n    pass\n")]);
+        let locked = root.join("locked");
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000)).unwrap();
+        if std::fs::read_dir(&locked).is_ok() {
+            std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+            eprintln!("SKIPPED unreadable_directory_is_reported_and_skipped: permission bits are not enforced for this user (e.g. root)");
+            return;
+        }
+
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        let result = indexer.initial_index();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        result.unwrap();
+        assert!(indexer.file_events(&root.join("app.py")).is_some());
+        assert!(indexer.file_events(&locked.join("hidden.py")).is_none());
+        assert_eq!(indexer.walk_errors().len(), 1);
+        assert_eq!(indexer.walk_errors()[0].0, locked);
+        assert_eq!(indexer.walk_errors()[0].1.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn cargo_manifest_enables_rust_and_ignores_target() {
+        let (_dir, root) = project(&[
+            ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            ("src/main.rs", "fn main() {}\n"),
+            ("target/debug/build.rs", "fn generated() {}\n"),
+        ]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_extensions(vec!["py"])
+            .with_manifest_detection()
+            .unwrap();
+
+        indexer.initial_index().unwrap();
+
+        assert!(indexer.file_events(&root.join("src/main.rs")).is_some());
+        assert!(indexer.file_events(&root.join("target/debug/build.rs")).is_none());
+    }
+
+    #[test]
+    fn pyproject_manifest_enables_stub_files() {
+        let (_dir, root) = project(&[("pyproject.toml", "[project]\nname = \"demo\"\n"), ("api.pyi", "def f() -> int: ...\n")]);
+        let mut plain = FileIndexer::from_root_project(&root).unwrap();
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_manifest_detection().unwrap();
+
+        assert!(!plain.index_decider.should_index(root.join("api.pyi")));
+        assert!(indexer.index_decider.should_index(root.join("api.pyi")));
+        assert!(!indexer.index_decider.should_index(root.join("pkg/__pycache__/api.py")));
+    }
+
+    #[test]
+    fn clear_empties_the_index_but_keeps_the_config() {
+        let (_dir, root) = project(&[("app.py", "def run():\n    pass\n"), ("lib.rs", "fn helper() {}\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_extensions(vec!["py"]).with_reparse_on_rename(false);
+        let app = root.join("app.py");
+        indexer.initial_index().unwrap();
+        indexer.set_metadata(&app, "owner", "core");
+        let moved_away = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From))).add_path(root.join("gone.py")).set_tracker(3);
+        indexer.handle_event(moved_away).unwrap();
+        indexer.pending_parses.push_back(app.clone());
+        assert_eq!(indexer.symbol_index().find_definition("run").len(), 1);
+        assert!(!indexer.pending_renames.is_empty());
+
+        indexer.clear();
+
+        assert!(indexer.file_events(&app).is_none() && indexer.indexed_files.is_empty());
+        assert!(indexer.symbol_index().find_definition("run").is_empty());
+        assert!(indexer.pending_parses.is_empty() && indexer.pending_renames.is_empty());
+        assert_eq!(indexer.get_metadata(&app, "owner"), None);
+        assert!(!indexer.reparse_on_rename);
+
+        indexer.initial_index().unwrap();
+        assert!(indexer.file_events(&app).is_some());
+        assert!(indexer.file_events(&root.join("lib.rs")).is_none());
+    }
+
+    #[test]
+    fn rate_limit_spreads_a_burst_without_dropping_parses() {
+        let files: Vec<(String, &str)> = (0..6).map(|i| (format!("m{i}.py"), "x = 1\n")).collect();
+        let files: Vec<(&str, &str)> = files.iter().map(|(name, content)| (name.as_str(), *content)).collect();
+        let (_dir, root) = project(&files);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_max_parses_per_second(4);
+        let parse_times = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = std::rc::Rc::clone(&parse_times);
+        indexer.add_observer(Box::new(move |_| recorder.borrow_mut().push(Instant::now())));
+
+        let start = Instant::now();
+        for (name, _) in &files {
+            indexer.request_index(&root.join(name)).unwrap();
+        }
+        while let Some(delay) = indexer.pending_parse_delay() {
+            std::thread::sleep(delay);
+            indexer.process_pending_parses();
+        }
+
+        let parse_times = parse_times.borrow();
+        assert_eq!(parse_times.len(), 6);
+        assert_eq!(parse_times.iter().filter(|time| time.duration_since(start) < Duration::from_millis(900)).count(), 4);
+        assert!(parse_times[5].duration_since(start) >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn index_paths_parses_exactly_the_given_files() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n"), ("b.py", "y = 2\n"), ("c.py", "z = 3\n"), ("notes.txt", "text\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        let parsed = parsed_files(&mut indexer);
+
+        indexer.index_paths(&[root.join("a.py"), root.join("c.py"), root.join("notes.txt")]).unwrap();
+
+        assert_eq!(*parsed.borrow(), vec![root.join("a.py"), root.join("c.py")]);
+        assert!(indexer.file_events(&root.join("b.py")).is_none());
+    }
+
+    #[test]
+    fn file_changed_during_the_walk_is_reindexed() {
+        let (_dir, root) = project(&[("a.py", "def before():\n    pass\n"), ("b.py", "x = 1\n")]);
+        let target = root.join("a.py");
+        let indexed = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = std::rc::Rc::clone(&indexed);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_index_threads(1)
+            .with_on_file_indexed(move |path, _| {
+                recorder.borrow_mut().push(path.to_path_buf());
+                if path.ends_with("a.py") && recorder.borrow().len() == 1 {
+                    // Stands in for an editor saving mid-walk; the later mtime avoids filesystem timestamp granularity.
+                    std::fs::write(path, "def after():\n    pass\n").unwrap();
+                    let file = std::fs::File::options().write(true).open(path).unwrap();
+                    file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+                }
+            });
+
+        indexer.initial_index().unwrap();
+
+        let names: Vec<String> = indexer.file_events(&target).unwrap().functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["after"]);
+        assert_eq!(indexed.borrow().iter().filter(|path| **path == target).count(), 2);
+    }
+
+    #[test]
+    fn languages_present_lists_each_language_once() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n"), ("b.py", "y = 2\n"), ("lib.rs", "fn helper() {}\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+
+        indexer.initial_index().unwrap();
+
+        assert_eq!(indexer.languages_present(), vec!["python", "rust"]);
+    }
+
+    #[test]
+    fn summary_only_counts_match_a_full_parse() {
+        let source = "class Shape:\n    def area(self):\n        return 0\n\ndef load():\n    pass\n\ndef save():\n    pass\n";
+        let (_dir, root) = project(&[("shapes.py", source)]);
+        let path = root.join("shapes.py");
+        let mut full = FileIndexer::from_root_project(&root).unwrap();
+        let mut summary_only = FileIndexer::from_root_project(&root).unwrap().with_summary_only();
+
+        full.initial_index().unwrap();
+        summary_only.initial_index().unwrap();
+
+        let file_events = full.file_events(&path).unwrap();
+        let summary = &summary_only.file_summaries()[&path];
+        assert!(summary_only.file_events(&path).is_none());
+        assert_eq!(summary.language, crate::parser::event::Language::Python);
+        assert_eq!(summary.function_count, file_events.functions().count());
+        assert_eq!(summary.class_count, file_events.classes().count());
+        assert_eq!(summary.line_count, file_events.line_count);
+        assert_eq!((summary.function_count, summary.class_count, summary.line_count), (3, 1, 9));
+    }
+
+    // A native backend that can't start, as on filesystems without change notifications.
+    struct FailingWatcher;
+
+    impl Watcher for FailingWatcher {
+        fn new<F: notify::EventHandler>(_event_handler: F, _config: Config) -> notify::Result<Self> {
+            Err(notify::Error::generic("native watching unsupported"))
+        }
+
+        fn watch(&mut self, _path: &Path, _recursive_mode: RecursiveMode) -> notify::Result<()> {
+            Ok(())
+        }
+
+        fn unwatch(&mut self, _path: &Path) -> notify::Result<()> {
+            Ok(())
+        }
+
+        fn kind() -> notify::WatcherKind {
+            notify::WatcherKind::NullWatcher
+        }
+    }
+
+    #[test]
+    fn failing_native_watcher_falls_back_to_polling() {
+        let (_dir, root) = project(&[("app.py", "x = 1\n")]);
+        let indexer = FileIndexer::from_root_project(&root).unwrap().with_poll_interval(Duration::from_millis(50));
+
+        let (watcher, rx) = indexer.setup_watcher_with::<FailingWatcher>().unwrap();
+        assert!(watcher.downcast_ref::<PollWatcher>().is_some());
+
+        std::fs::write(root.join("new.py"), "y = 2\n").unwrap();
+        let event = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert!(event.paths.contains(&root.join("new.py")));
+    }
+
+    #[test]
+    fn forced_native_watcher_reports_its_failure() {
+        let (_dir, root) = project(&[("app.py", "x = 1\n")]);
+        let indexer = FileIndexer::from_root_project(&root).unwrap().with_watcher_backend(WatcherBackend::Native);
+
+        assert!(indexer.setup_watcher_with::<FailingWatcher>().is_err());
+    }
+
+    // Takes a fixed time per file, so parse throughput is far below how fast events can be handled.
+    struct SlowParser;
+
+    const SLOW_PARSE: Duration = Duration::from_millis(100);
+
+    impl LanguageParser for SlowParser {
+        fn language(&self) -> tree_sitter::Language {
+            tree_sitter_python::language()
+        }
+
+        fn language_name(&self) -> &'static str {
+            "slow"
+        }
+
+        fn file_extensions(&self) -> &[&'static str] {
+            &["slow"]
+        }
+
+        fn walk_tree(&self, _node: &tree_sitter::Node, _source_code: &str, _file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+            thread::sleep(SLOW_PARSE);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn event_flood_is_drained_while_parses_run_on_workers() {
+        let files: Vec<String> = (0..10).map(|i| format!("f{i}.slow")).collect();
+        let (_dir, root) = project(&files.iter().map(|name| (name.as_str(), "x = 1\n")).collect::<Vec<_>>());
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_extensions(vec!["slow"])
+            .with_parser(Box::new(SlowParser));
+        indexer.parse_pool = Some(ParsePool::new(2, indexer.source_reader.clone()));
+        let parses = parse_counter(&mut indexer);
+
+        let (tx, rx) = channel::<Result<Event, notify::Error>>();
+        for name in &files {
+            tx.send(Ok(Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(root.join(name)))).unwrap();
+        }
+        drop(tx);
+
+        let started = Instant::now();
+        for received in rx.iter() {
+            indexer.handle_event(received.unwrap()).unwrap();
+        }
+        assert!(started.elapsed() < SLOW_PARSE, "handling took {:?}", started.elapsed());
+        assert_eq!(parses.get(), 0);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while parses.get() < files.len() && Instant::now() < deadline {
+            thread::sleep(PARSE_RESULT_POLL_INTERVAL);
+            indexer.collect_parse_results();
+        }
+        assert_eq!(parses.get(), files.len());
+    }
+
+    #[test]
+    fn editing_gitignore_reloads_rules_without_parsing_it() {
+        let (_dir, root) = project(&[(".gitignore", "*.log\n"), ("a.py", "x = 1\n"), ("b.py", "y = 2\n")]);
+        let (gitignore, b) = (root.join(".gitignore"), root.join("b.py"));
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        let parsed = parsed_files(&mut indexer);
+        let modified = |path: &Path| Event::new(EventKind::Modify(ModifyKind::Data(notify::event::DataChange::Content))).add_path(path.to_path_buf());
+        assert!(indexer.file_events(&b).is_some());
+
+        std::fs::write(&gitignore, "*.log\nb.py\n").unwrap();
+        indexer.handle_event(modified(&gitignore)).unwrap();
+        assert!(indexer.file_events(&b).is_none());
+        assert!(indexer.file_events(&root.join("a.py")).is_some());
+
+        std::fs::write(&gitignore, "*.log\n").unwrap();
+        indexer.handle_event(modified(&gitignore)).unwrap();
+        assert!(indexer.file_events(&b).is_some());
+
+        assert!(!parsed.borrow().contains(&gitignore));
+        assert_eq!(*parsed.borrow(), vec![b]);
+    }
+
+    #[test]
+    fn touching_gitignore_without_changing_it_reindexes_nothing() {
+        let (_dir, root) = project(&[(".gitignore", "*.log\n"), ("a.py", "x = 1\n")]);
+        let gitignore = root.join(".gitignore");
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        let parsed = parsed_files(&mut indexer);
+
+        std::fs::write(&gitignore, "*.log\n").unwrap();
+        indexer.handle_event(Event::new(EventKind::Modify(ModifyKind::Data(notify::event::DataChange::Content))).add_path(gitignore)).unwrap();
+
+        assert!(parsed.borrow().is_empty());
+        assert!(indexer.file_events(&root.join("a.py")).is_some());
+    }
+
+    #[test]
+    fn metadata_follows_a_rename_and_goes_with_a_delete() {
+        let (_dir, root) = project(&[("old.py", "x = 1\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        let (from, to) = (root.join("old.py"), root.join("new.py"));
+        indexer.set_metadata(&from, "tab", "3");
+        let (_watcher, rx) = indexer.setup_watcher().unwrap();
+
+        std::fs::rename(&from, &to).unwrap();
+        handle_events_until_quiet(&mut indexer, &rx, Duration::from_millis(500));
+        indexer.flush_unpaired_renames();
+
+        assert_eq!(indexer.get_metadata(&from, "tab"), None);
+        assert_eq!(indexer.get_metadata(&to, "tab"), Some("3"));
+
+        std::fs::remove_file(&to).unwrap();
+        handle_events_until_quiet(&mut indexer, &rx, Duration::from_millis(500));
+
+        assert_eq!(indexer.get_metadata(&to, "tab"), None);
+    }
+
+    #[test]
+    fn deleted_file_leaves_the_index() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+        indexer.initial_index().unwrap();
+        indexer.index_decider.clear_debouncer();
+        let created = root.join("created.py");
+
+        std::fs::write(&created, "def fresh():\n    pass\n").unwrap();
+        indexer.handle_event(Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(created.clone())).unwrap();
+        assert!(indexer.file_events(&created).is_some());
+
+        std::fs::remove_file(&created).unwrap();
+        assert!(indexer.delete_file(&created));
+
+        assert!(indexer.file_events(&created).is_none());
+        assert!(!indexer.indexed_files.contains(&created));
+        assert!(!indexer.delete_file(&created));
+    }
+
+    #[test]
+    fn data_and_any_modify_for_one_path_are_coalesced() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n")]);
+        let path = root.join("a.py");
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_modify_coalescing(Duration::from_millis(200));
+        indexer.initial_index().unwrap();
+        // Without the decider's own debounce window, only the coalescer can merge the pair.
+        indexer.index_decider = IndexDecider::new(
+            vec![IgnoreMatcher::from_root_project(&root, Vec::new()).unwrap()],
+            ExtensionFilter::new(DEFAULT_EXTENSIONS.to_vec()),
+            Debouncer::new(0, 0),
+        );
+        let parses = parse_counter(&mut indexer);
+        let modify = |kind: ModifyKind| Event::new(EventKind::Modify(kind)).add_path(path.clone());
+
+        indexer.handle_event(modify(ModifyKind::Data(notify::event::DataChange::Content))).unwrap();
+        indexer.handle_event(modify(ModifyKind::Any)).unwrap();
+        assert_eq!(parses.get(), 1);
+
+        thread::sleep(Duration::from_millis(250));
+        indexer.handle_event(modify(ModifyKind::Any)).unwrap();
+        assert_eq!(parses.get(), 2);
+    }
+
+    #[test]
+    fn on_file_indexed_fires_once_per_file_with_its_events() {
+        let (_dir, root) = project(&[("a.py", "def alpha():\n    pass\n"), ("pkg/b.py", "def beta():\n    pass\n\ndef gamma():\n    pass\n"), ("notes.txt", "text\n")]);
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorder = std::rc::Rc::clone(&seen);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_on_file_indexed(move |path, file_events| {
+                recorder.borrow_mut().push((path.to_path_buf(), format!("{:?}", file_events)));
+            });
+
+        indexer.initial_index().unwrap();
+
+        let mut seen = seen.borrow().clone();
+        seen.sort();
+        let paths: Vec<&PathBuf> = seen.iter().map(|(path, _)| path).collect();
+        assert_eq!(paths, vec![&root.join("a.py"), &root.join("pkg/b.py")]);
+        for (path, events) in &seen {
+            assert_eq!(*events, format!("{:?}", indexer.file_events(path).unwrap()));
+        }
+    }
+
+    #[test]
+    fn file_over_the_line_limit_is_tracked_but_not_parsed() {
+        let long_file = "x = 1\n".repeat(50);
+        let (_dir, root) = project(&[("long.py", long_file.as_str()), ("short.py", "def kept():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_max_lines(20);
+        let parsed = parsed_files(&mut indexer);
+
+        indexer.initial_index().unwrap();
+
+        let long = root.join("long.py");
+        assert_eq!(*parsed.borrow(), vec![root.join("short.py")]);
+        assert!(indexer.file_events(&long).is_none());
+        assert!(indexer.indexed_files.contains(&long));
+
+        std::fs::remove_file(&long).unwrap();
+        assert!(indexer.delete_file(&long));
+        assert!(!indexer.indexed_files.contains(&long));
+    }
+
+    #[test]
+    fn reconciliation_sweep_reindexes_a_modification_without_an_event() {
+        let (_dir, root) = project(&[("share.py", "def before():\n    pass\n")]);
+        let target = root.join("share.py");
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_network_mode(Duration::from_millis(50));
+        indexer.initial_index().unwrap();
+        indexer.index_decider.clear_debouncer();
+        indexer.reconcile_if_due();
+        let parses = parse_counter(&mut indexer);
+
+        // No event is handed to the indexer; the later mtime avoids filesystem timestamp granularity.
+        std::fs::write(&target, "def after():\n    pass\n").unwrap();
+        let file = std::fs::File::options().write(true).open(&target).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        indexer.reconcile_if_due();
+        assert_eq!(parses.get(), 0);
+
+        thread::sleep(Duration::from_millis(60));
+        indexer.reconcile_if_due();
+
+        assert_eq!(parses.get(), 1);
+        let names: Vec<String> = indexer.file_events(&target).unwrap().functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, .. } => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["after"]);
+    }
+
+    #[test]
+    fn reconciliation_sweep_picks_up_created_and_vanished_files() {
+        let (_dir, root) = project(&[("kept.py", "x = 1\n"), ("gone.py", "def old():\n    pass\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_network_mode(Duration::from_millis(20));
+        indexer.initial_index().unwrap();
+        indexer.reconcile_if_due();
+
+        std::fs::remove_file(root.join("gone.py")).unwrap();
+        std::fs::write(root.join("new.py"), "def fresh():\n    pass\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "not source\n").unwrap();
+        thread::sleep(Duration::from_millis(30));
+        indexer.reconcile_if_due();
+
+        assert!(!indexer.indexed_files.contains(&root.join("gone.py")));
+        assert!(indexer.symbol_index().find_definition("old").is_empty());
+        assert!(indexer.indexed_files.contains(&root.join("new.py")));
+        assert_eq!(indexer.symbol_index().find_definition("fresh").len(), 1);
+        assert!(!indexer.indexed_files.contains(&root.join("notes.txt")));
+    }
+
+    #[test]
+    fn reconciliation_sweep_goes_through_the_rate_limiter() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n"), ("b.py", "y = 2\n")]);
+        let mut indexer = FileIndexer::from_root_project(&root).unwrap()
+            .with_network_mode(Duration::from_millis(20))
+            .with_max_parses_per_second(1);
+        indexer.initial_index().unwrap();
+        indexer.index_decider.clear_debouncer();
+        indexer.reconcile_if_due();
+        let parsed = parsed_files(&mut indexer);
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        for name in ["a.py", "b.py"] {
+            std::fs::File::options().write(true).open(root.join(name)).unwrap().set_modified(later).unwrap();
+        }
+        thread::sleep(Duration::from_millis(30));
+        indexer.reconcile_if_due();
+
+        assert_eq!(parsed.borrow().len(), 1);
+        assert!(indexer.pending_parse_delay().is_some());
+
+        // A queued file isn't re-queued by the next sweep; it is parsed once the limiter allows.
+        thread::sleep(Duration::from_millis(30));
+        indexer.reconcile_if_due();
+        while let Some(delay) = indexer.pending_parse_delay() {
+            thread::sleep(delay);
+            indexer.process_pending_parses();
+        }
+        let mut parsed = parsed.borrow().clone();
+        parsed.sort();
+        assert_eq!(parsed, vec![root.join("a.py"), root.join("b.py")]);
+    }
+
+    #[test]
+    fn parallel_initial_index_matches_the_sequential_one() {
+        let sources: Vec<(String, String)> = (0..40)
+            .map(|i| (format!("pkg{}/module_{}.py", i % 4, i), format!("import os\n\nclass Model{i}:\n    def method(self, value: int = {i}):\n        return value\n\ndef helper_{i}():\n    for item in range({i}):\n        yield item\n")))
+            .collect();
+        let files: Vec<(&str, &str)> = sources.iter().map(|(name, content)| (name.as_str(), content.as_str())).collect();
+        let (_dir, root) = project(&files);
+
+        let index_with = |threads: usize| {
+            let mut indexer = FileIndexer::from_root_project(&root).unwrap().with_index_threads(threads);
+            indexer.initial_index().unwrap();
+            let mut events: Vec<(PathBuf, String)> = indexer.all_file_events.iter()
+                // Everything but parse_timestamp, which differs between any two runs.
+                .map(|(path, file_events)| (path.clone(), format!("{:?} {:?} {} {:?}", file_events.events, file_events.diagnostics, file_events.line_count, file_events.last_modified)))
+                .collect();
+            events.sort();
+            (indexer.indexed_files.clone(), events)
+        };
+
+        let (sequential_files, sequential_events) = index_with(1);
+        let (parallel_files, parallel_events) = index_with(4);
+
+        assert_eq!(sequential_events.len(), 40);
+        assert_eq!(parallel_files, sequential_files);
+        assert_eq!(parallel_events, sequential_events);
+    }
+
+    #[test]
+    fn stop_from_another_thread_ends_start_watching() {
+        let (_dir, root) = project(&[("a.py", "x = 1\n")]);
+        let (handle_tx, handle_rx) = channel();
+        let (done_tx, done_rx) = channel();
+
+        // The indexer holds non-Send callbacks, so it is built on the thread that runs it.
+        let watching = thread::spawn(move || {
+            let mut indexer = FileIndexer::from_root_project(&root).unwrap();
+            handle_tx.send(indexer.stop_handle()).unwrap();
+            let result = indexer.start_watching().map_err(|e| e.to_string());
+            done_tx.send(result).unwrap();
+        });
+
+        let stop_handle = handle_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        // Long enough for the initial index to finish and the event loop to be waiting.
+        thread::sleep(Duration::from_millis(300));
+        stop_handle.stop();
+
+        let result = done_rx.recv_timeout(STOP_POLL_INTERVAL + Duration::from_secs(5)).expect("start_watching did not return after stop");
+        assert_eq!(result, Ok(()));
+        watching.join().unwrap();
+    }
 }
@@ -1,26 +1,208 @@
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
 
 pub struct IgnoreMatcher {
     matcher: Gitignore,
+    // Ignore files found below the root, deepest directory first, each matching relative to its own directory.
+    nested_matchers: Vec<Gitignore>,
+    root: PathBuf,
+    ignore_files: Vec<String>,
+    user_ignores: Vec<String>,
+    // The ignore files the rules were built from and their contents, to tell an edit from a touch.
+    rule_sources: Vec<(PathBuf, String)>,
 }
 
 impl IgnoreMatcher {
-    pub fn from_root_project<P: AsRef<Path>>(root: P, user_ignores: Vec<&str>) -> Self{
+    pub fn from_root_project<P: AsRef<Path>>(root: P, user_ignores: Vec<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_ignore_files(root, DEFAULT_IGNORE_FILES.to_vec(), user_ignores)
+    }
+
+    pub fn with_ignore_files<P: AsRef<Path>>(root: P, ignore_files: Vec<&str>, user_ignores: Vec<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        let root = root.as_ref().to_path_buf();
+        let ignore_files: Vec<String> = ignore_files.into_iter().map(|s| s.to_string()).collect();
+        let user_ignores: Vec<String> = user_ignores.into_iter().map(|s| s.to_string()).collect();
+
+        let matcher = Self::build_matcher(&root, &ignore_files, &user_ignores)?;
+        let nested_matchers = Self::build_nested_matchers(&root, &matcher, &ignore_files)?;
+        let rule_sources = Self::read_rule_sources(&root, &nested_matchers, &ignore_files);
+        Ok(Self {matcher, nested_matchers, root, ignore_files, user_ignores, rule_sources})
+    }
+
+    fn build_matcher(root: &Path, ignore_files: &[String], user_ignores: &[String]) -> Result<Gitignore, ignore::Error> {
         let mut ignore_builder = GitignoreBuilder::new(root);
 
-        let _ = ignore_builder.add(".gitignore");
-        let _ = ignore_builder.add(".ignore");
+        for file_name in ignore_files {
+            let _ = ignore_builder.add(root.join(file_name));
+        }
 
-        for file_name in user_ignores {
-            let _ = ignore_builder.add_line(None, &file_name);
+        for pattern in user_ignores {
+            let _ = ignore_builder.add_line(None, pattern);
         }
 
-        let matcher = ignore_builder.build().unwrap();
-        Self {matcher}
+        ignore_builder.build()
+    }
+
+    // Directories the rules above them already exclude aren't searched, the same as Git does.
+    fn build_nested_matchers(root: &Path, root_matcher: &Gitignore, ignore_files: &[String]) -> Result<Vec<Gitignore>, ignore::Error> {
+        let mut nested_matchers: Vec<Gitignore> = Vec::new();
+        let mut pending_dirs: Vec<PathBuf> = vec![root.to_path_buf()];
+
+        while let Some(dir) = pending_dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_dir() || entry.file_name() == ".git" {
+                    continue;
+                }
+
+                let is_ignored = root_matcher.matched(&path, true).is_ignore()
+                    || Self::nested_match(&nested_matchers, &path, true).is_some_and(|ignored| ignored);
+                if is_ignored {
+                    continue;
+                }
+
+                let mut ignore_builder = GitignoreBuilder::new(&path);
+                let mut has_ignore_file = false;
+                for file_name in ignore_files {
+                    let ignore_file = path.join(file_name);
+                    if ignore_file.is_file() {
+                        let _ = ignore_builder.add(ignore_file);
+                        has_ignore_file = true;
+                    }
+                }
+                if has_ignore_file {
+                    let depth = path.components().count();
+                    let position = nested_matchers.partition_point(|matcher| matcher.path().components().count() >= depth);
+                    nested_matchers.insert(position, ignore_builder.build()?);
+                }
+
+                pending_dirs.push(path);
+            }
+        }
+
+        Ok(nested_matchers)
+    }
+
+    // The closest ignore file with an opinion decides: Some(true) ignores, Some(false) re-includes (`!pattern`).
+    fn nested_match(nested_matchers: &[Gitignore], path: &Path, is_dir: bool) -> Option<bool> {
+        nested_matchers.iter()
+            .filter(|matcher| path.starts_with(matcher.path()))
+            .map(|matcher| matcher.matched(path, is_dir))
+            .find(|matched| !matched.is_none())
+            .map(|matched| matched.is_ignore())
+    }
+
+    // Re-reads the ignore files from disk; the underlying Gitignore can't be updated in place.
+    // Returns whether any of them changed. On failure the previous rules stay in effect.
+    pub fn reload(&mut self) -> Result<bool, ignore::Error> {
+        let matcher = Self::build_matcher(&self.root, &self.ignore_files, &self.user_ignores)?;
+        let nested_matchers = Self::build_nested_matchers(&self.root, &matcher, &self.ignore_files)?;
+        let rule_sources = Self::read_rule_sources(&self.root, &nested_matchers, &self.ignore_files);
+
+        let changed = rule_sources != self.rule_sources;
+        self.matcher = matcher;
+        self.nested_matchers = nested_matchers;
+        self.rule_sources = rule_sources;
+        Ok(changed)
+    }
+
+    fn read_rule_sources(root: &Path, nested_matchers: &[Gitignore], ignore_files: &[String]) -> Vec<(PathBuf, String)> {
+        std::iter::once(root)
+            .chain(nested_matchers.iter().map(|matcher| matcher.path()))
+            .flat_map(|dir| ignore_files.iter().map(move |file_name| dir.join(file_name)))
+            .filter_map(|path| std::fs::read_to_string(&path).ok().map(|content| (path, content)))
+            .collect()
+    }
+
+    // Gitignore is immutable, so the root matcher is rebuilt with the extra pattern. An invalid
+    // pattern is rejected and leaves the current rules untouched.
+    pub fn add_pattern(&mut self, pattern: &str) -> Result<(), ignore::Error> {
+        GitignoreBuilder::new(&self.root).add_line(None, pattern)?;
+
+        let mut user_ignores = self.user_ignores.clone();
+        user_ignores.push(pattern.to_string());
+        self.matcher = Self::build_matcher(&self.root, &self.ignore_files, &user_ignores)?;
+        self.user_ignores = user_ignores;
+        Ok(())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn ignore_files(&self) -> &[String] {
+        &self.ignore_files
+    }
+
+    pub fn user_ignores(&self) -> &[String] {
+        &self.user_ignores
     }
 
+    pub fn preview(&self, candidate_pattern: &str, paths: &[PathBuf]) -> Result<Vec<PathBuf>, ignore::Error> {
+        let mut user_ignores = self.user_ignores.clone();
+        user_ignores.push(candidate_pattern.to_string());
+        let candidate_matcher = Self::build_matcher(&self.root, &self.ignore_files, &user_ignores)?;
+
+        Ok(paths.iter()
+            .filter(|path| !self.is_ignored(path) && candidate_matcher.matched(path, false).is_ignore())
+            .cloned()
+            .collect())
+    }
+
+    // A file is also ignored when a directory above it is, e.g. anything under `node_modules/`.
     pub fn is_ignored<P: AsRef<Path>>(&self, path: P) -> bool{
-        self.matcher.matched(path, false).is_ignore()
+        let path = path.as_ref();
+        self.matches(path, false)
+            || path.ancestors()
+                .skip(1)
+                .take_while(|dir| dir.starts_with(&self.root) && *dir != self.root)
+                .any(|dir| self.matches(dir, true))
     }
-}
\ No newline at end of file
+
+    // Only the directory's own rules are checked; callers walking the tree have already checked its parents.
+    pub fn is_ignored_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.matches(path.as_ref(), true)
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        Self::nested_match(&self.nested_matchers, path, is_dir)
+            .unwrap_or_else(|| self.matcher.matched(path, is_dir).is_ignore())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_ignore_file_patterns_take_effect() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(".myignore"), "*.log\n").unwrap();
+
+        let matcher = IgnoreMatcher::with_ignore_files(root.path(), vec![".myignore"], Vec::new()).unwrap();
+
+        assert!(matcher.is_ignored(root.path().join("build.log")));
+        assert!(!matcher.is_ignored(root.path().join("main.py")));
+    }
+
+    #[test]
+    fn preview_returns_only_paths_the_pattern_would_exclude() {
+        let root = tempfile::tempdir().unwrap();
+        let matcher = IgnoreMatcher::from_root_project(root.path(), Vec::new()).unwrap();
+        let paths = vec![
+            root.path().join("app.log"),
+            root.path().join("main.py"),
+            root.path().join("logs/server.log"),
+            root.path().join("README.md"),
+        ];
+
+        let excluded = matcher.preview("*.log", &paths).unwrap();
+
+        assert_eq!(excluded, vec![root.path().join("app.log"), root.path().join("logs/server.log")]);
+    }
+}
@@ -1,30 +1,97 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path};
 use std::time::Duration;
 use crate::ignore_matcher::IgnoreMatcher; 
 use crate::debouncer::Debouncer;
 use crate::extension_filter::ExtensionFilter;
 
+// Enough for any real shebang line without reading far into a file that has no newline.
+const FIRST_LINE_LIMIT: u64 = 256;
+
 pub struct IndexDecider {
-    ignore_matcher: IgnoreMatcher,
+    // One per root, since ignore files are resolved relative to the root they were found in.
+    ignore_matchers: Vec<IgnoreMatcher>,
     extension_filter: ExtensionFilter,
     debouncer: Debouncer,
 }
 
 impl IndexDecider {
-    pub fn new(ignore_matcher: IgnoreMatcher, extension_filter: ExtensionFilter, debouncer: Debouncer) -> Self {
+    pub fn new(ignore_matchers: Vec<IgnoreMatcher>, extension_filter: ExtensionFilter, debouncer: Debouncer) -> Self {
         Self {
-            ignore_matcher,
+            ignore_matchers,
             extension_filter,
             debouncer,
         }
     }
 
     pub fn should_index<P: AsRef<Path>>(&mut self, path: P) -> bool {
-        !self.ignore_matcher.is_ignored(path.as_ref()) 
-        && self.extension_filter.is_supported(path.as_ref()) 
+        self.is_indexable(path.as_ref())
         && self.debouncer.should_index(path.as_ref())
     }
 
+    // should_index without the debouncer, for looking at a path without counting as indexing it.
+    pub fn is_indexable<P: AsRef<Path>>(&self, path: P) -> bool {
+        !self.is_ignored(path.as_ref())
+        && self.is_supported(path.as_ref())
+    }
+
+    // Only extensionless files have their first line read, so binaries like `.png` are never opened.
+    fn is_supported(&self, path: &Path) -> bool {
+        if self.extension_filter.is_supported(path) {
+            return true;
+        }
+        if path.extension().is_some() {
+            return false;
+        }
+
+        let mut first_line = String::new();
+        let read = File::open(path)
+            .and_then(|file| BufReader::new(file.take(FIRST_LINE_LIMIT)).read_line(&mut first_line));
+        read.is_ok() && self.extension_filter.is_supported_by_content(path, &first_line)
+    }
+
+    pub fn is_ignored<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.ignore_matcher_for(path.as_ref()).is_some_and(|matcher| matcher.is_ignored(path.as_ref()))
+    }
+
+    pub fn is_ignored_dir<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.ignore_matcher_for(path.as_ref()).is_some_and(|matcher| matcher.is_ignored_dir(path.as_ref()))
+    }
+
+    // The innermost root containing the path decides, so nested roots keep their own rules. A path
+    // under none of the roots falls back to the first one, which is all there is for a single root.
+    pub fn ignore_matcher_for(&self, path: &Path) -> Option<&IgnoreMatcher> {
+        self.ignore_matchers.iter()
+            .filter(|matcher| path.starts_with(matcher.root()))
+            .max_by_key(|matcher| matcher.root().components().count())
+            .or(self.ignore_matchers.first())
+    }
+
+    pub fn ignore_matchers(&self) -> &[IgnoreMatcher] {
+        &self.ignore_matchers
+    }
+
+    pub fn ignore_matchers_mut(&mut self) -> &mut [IgnoreMatcher] {
+        &mut self.ignore_matchers
+    }
+
+    pub fn extension_filter_mut(&mut self) -> &mut ExtensionFilter {
+        &mut self.extension_filter
+    }
+
+    pub fn set_extension_filter(&mut self, extension_filter: ExtensionFilter) {
+        self.extension_filter = extension_filter;
+    }
+
+    pub fn set_ignore_matchers(&mut self, ignore_matchers: Vec<IgnoreMatcher>) {
+        self.ignore_matchers = ignore_matchers;
+    }
+
+    pub fn clear_debouncer(&mut self) {
+        self.debouncer.clear();
+    }
+
     pub fn debounce_duration_left<P: AsRef<Path>>(&self, path: P) -> Duration{
         self.debouncer.time_left(path)
     }
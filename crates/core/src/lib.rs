@@ -0,0 +1,22 @@
+pub mod file_watcher;
+pub mod extension_filter;
+pub mod ignore_matcher;
+pub mod index_decider;
+pub mod manifest;
+pub mod debouncer;
+pub mod error;
+pub mod parser;
+pub mod rate_limiter;
+pub mod symbol_index;
+pub mod api_diff;
+pub mod source_reader;
+pub mod parse_pool;
+
+use std::path::Path;
+
+pub use crate::file_watcher::{FileIndexer, StopHandle};
+
+// Indexes `root` and then watches it until the watcher's channel closes or the indexer is stopped.
+pub fn run<P: AsRef<Path>>(root: P) -> Result<(), Box<dyn std::error::Error>> {
+    FileIndexer::from_root_project(root)?.start_watching()
+}
@@ -1,17 +1,43 @@
-use crate::file_watcher::FileIndexer;
-
-/* 
-    Coming soon
-*/
-mod file_watcher;
-mod extension_filter;
-mod ignore_matcher;
-mod index_decider;
-mod debouncer;
-mod parser;
+use clap::Parser;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use cortex::FileIndexer;
+
+#[derive(Parser)]
+#[command(about = "Index a project and keep the index up to date as files change")]
+struct Cli {
+    /// Project root to index and watch (repeatable)
+    #[arg(long = "root", value_name = "PATH", default_value = ".")]
+    roots: Vec<PathBuf>,
+
+    /// Extension to index (repeatable); replaces the default list
+    #[arg(long = "ext", value_name = "EXT")]
+    extensions: Vec<String>,
+
+    /// Gitignore-style pattern to exclude (repeatable)
+    #[arg(long = "ignore", value_name = "PATTERN")]
+    ignores: Vec<String>,
+
+    /// Poll for changes at this interval instead of using native file notifications
+    #[arg(long, value_name = "MS")]
+    poll_interval_ms: Option<u64>,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>>{
-    //simple check if it works
-    let mut indexer = FileIndexer::from_root_project(r"");
-    indexer.start_watching() 
-}   
\ No newline at end of file
+    let cli = Cli::parse();
+
+    let mut indexer = FileIndexer::from_roots(cli.roots)?
+        .with_ignore_patterns(cli.ignores.iter().map(String::as_str).collect())?;
+    if !cli.extensions.is_empty() {
+        indexer = indexer.with_extensions(cli.extensions.iter().map(String::as_str).collect());
+    }
+    if let Some(interval) = cli.poll_interval_ms {
+        indexer = indexer.with_poll_interval(Duration::from_millis(interval));
+    }
+
+    let stop_handle = indexer.stop_handle();
+    ctrlc::set_handler(move || stop_handle.stop())?;
+
+    indexer.start_watching()
+}
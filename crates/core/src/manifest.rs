@@ -0,0 +1,34 @@
+use std::path::Path;
+
+// Ignores are written as globs over the contents because IgnoreMatcher checks file paths, not directories.
+const MANIFESTS: [(&str, &[&str], &[&str]); 3] = [
+    ("Cargo.toml", &["rs"], &["/target/**"]),
+    ("package.json", &["js", "mjs", "cjs", "jsx", "ts", "tsx"], &["**/node_modules/**", "/dist/**"]),
+    ("pyproject.toml", &["py", "pyi", "pyw"], &["**/__pycache__/**", "/.venv/**", "**/*.egg-info/**"]),
+];
+
+#[derive(Debug, Default)]
+pub struct ManifestHints {
+    pub extensions: Vec<&'static str>,
+    pub ignores: Vec<&'static str>,
+}
+
+impl ManifestHints {
+    pub fn detect<P: AsRef<Path>>(root: P) -> Self {
+        let mut hints = Self::default();
+
+        for (manifest, extensions, ignores) in MANIFESTS {
+            if root.as_ref().join(manifest).is_file() {
+                println!("Detected {}, enabling {:?}", manifest, extensions);
+                hints.extensions.extend_from_slice(extensions);
+                hints.ignores.extend_from_slice(ignores);
+            }
+        }
+
+        hints
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.extensions.is_empty() && self.ignores.is_empty()
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::parser::event::FileEvents;
+use crate::source_reader::SourceReader;
+
+pub type ParseOutcome = (PathBuf, Result<Option<FileEvents>, String>);
+
+// Parses files off the watcher thread. The watcher only submits paths and later drains finished results.
+pub struct ParsePool {
+    jobs: Option<Sender<PathBuf>>,
+    results: Receiver<ParseOutcome>,
+    in_flight: HashSet<PathBuf>,
+    dirty: HashSet<PathBuf>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ParsePool {
+    pub fn new(threads: usize, reader: SourceReader) -> Self {
+        let (job_tx, job_rx) = channel::<PathBuf>();
+        let (result_tx, result_rx) = channel::<ParseOutcome>();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let jobs = Arc::clone(&job_rx);
+                let results = result_tx.clone();
+                let reader = reader.clone();
+
+                thread::spawn(move || loop {
+                    let job = {
+                        let Ok(receiver) = jobs.lock() else {
+                            break;
+                        };
+                        receiver.recv()
+                    };
+                    let Ok(path) = job else {
+                        break;
+                    };
+
+                    let outcome = reader.parse_source(&path).map_err(|e| e.to_string());
+                    if results.send((path, outcome)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            jobs: Some(job_tx),
+            results: result_rx,
+            in_flight: HashSet::new(),
+            dirty: HashSet::new(),
+            workers,
+        }
+    }
+
+    // A path already being parsed is re-queued once that parse finishes, so results never arrive out of order.
+    pub fn submit(&mut self, path: PathBuf) {
+        if self.in_flight.contains(&path) {
+            self.dirty.insert(path);
+            return;
+        }
+
+        if let Some(jobs) = &self.jobs
+            && jobs.send(path.clone()).is_ok() {
+            self.in_flight.insert(path);
+        }
+    }
+
+    pub fn has_in_flight(&self) -> bool {
+        !self.in_flight.is_empty()
+    }
+
+    pub fn try_recv(&mut self) -> Option<ParseOutcome> {
+        while let Ok((path, outcome)) = self.results.try_recv() {
+            self.in_flight.remove(&path);
+
+            if self.dirty.remove(&path) {
+                self.submit(path);
+                continue;
+            }
+
+            return Some((path, outcome));
+        }
+
+        None
+    }
+}
+
+impl Drop for ParsePool {
+    fn drop(&mut self) {
+        // Closing the job channel lets every worker's recv fail and exit its loop.
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -1,36 +1,55 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub enum ParseEvent {
     FunctionDefinition {
         name: String,
         start_line: usize,
+        start_column: usize,
         end_line: usize,
         parameters: Vec<String>,
+        parameters_detailed: Vec<Parameter>,
         return_type: Option<String>,
-        is_public: bool,
+        visibility: Visibility,
+        // An `async def` containing `yield` is an async generator, not a generator; at most one is set.
+        is_generator: bool,
+        is_async_generator: bool,
+        snippet: Option<String>,
+    },
+    
+    MacroDefinition {
+        name: String,
+        start_line: usize,
+        start_column: usize,
+        end_line: usize,
+        snippet: Option<String>,
     },
     
     ClassDefinition {
         name: String,
         start_line: usize,
+        start_column: usize,
         end_line: usize,
         fields: Vec<String>,
-        is_public: bool,
+        visibility: Visibility,
+        snippet: Option<String>,
     },
     
     VariableDefinition {
         name: String,
         var_type: Option<String>,
         line: usize,
-        is_public: bool,
+        column: usize,
+        visibility: Visibility,
         is_constant: bool,
+        snippet: Option<String>,
     },
     
     ImportStatement {
         module: String,
         items: Vec<String>, 
         line: usize,
+        column: usize,
         is_wildcard: bool,
     },
     
@@ -38,6 +57,7 @@ pub enum ParseEvent {
         condition_type: String, // "if", "elif", "else", "match", "switch"
         condition_summary: Option<String>,
         start_line: usize,
+        start_column: usize,
         end_line: usize,
     },
     
@@ -46,11 +66,13 @@ pub enum ParseEvent {
         iterator_variable: Option<String>,
         iterable: Option<String>,
         start_line: usize,
+        start_column: usize,
         end_line: usize,
     },
     
     TryBlock {
         start_line: usize,
+        start_column: usize,
         end_line: usize,
         exception_types: Vec<String>,
         has_finally: bool,
@@ -60,6 +82,7 @@ pub enum ParseEvent {
         caller_function: Option<String>, 
         callee: String,
         line: usize,
+        column: usize,
         arguments: Vec<String>, 
     },
     
@@ -67,13 +90,21 @@ pub enum ParseEvent {
         variable: String,
         access_type: AccessType,
         line: usize,
+        column: usize,
         context: Option<String>, 
     },
     
+    RaiseStatement {
+        exception_type: Option<String>,
+        line: usize,
+        column: usize,
+    },
+    
     ClassInheritance {
         child_class: String,
         parent_classes: Vec<String>,
         line: usize,
+        column: usize,
     },
     
     // Python
@@ -81,17 +112,20 @@ pub enum ParseEvent {
         target: String, 
         decorator: String,
         line: usize,
+        column: usize,
     },
     
     PythonAsyncFunction {
         function_name: String,
         line: usize,
+        column: usize,
     },
     
     PythonContextManager {
         variable: Option<String>,
         context_expression: String,
         line: usize,
+        column: usize,
     },
     
     PythonListComprehension {
@@ -99,27 +133,173 @@ pub enum ParseEvent {
         iterator_variable: String,
         iterable: String,
         line: usize,
+        column: usize,
+    },
+    
+    PythonMainGuard {
+        start_line: usize,
+        start_column: usize,
+        end_line: usize,
+    },
+    
+    PythonTopLevelCode {
+        line: usize,
+        column: usize,
+    },
+    
+    // `is_instance` separates `self.x = ...` in `__init__` from assignments in the class body.
+    PythonClassAttribute {
+        class_name: String,
+        attribute: String,
+        is_instance: bool,
+        line: usize,
+        column: usize,
+    },
+    
+    // Go
+    GoMethodReceiver {
+        method: String,
+        receiver_type: String,
+        is_pointer: bool,
+        line: usize,
+        column: usize,
     },
     
     DocComment {
         target: String, 
         content: String,
         line: usize,
+        column: usize,
         doc_type: DocType,
     },
     
     Comment {
         content: String,
         line: usize,
+        column: usize,
         comment_type: CommentType,
     },
 }
 
+impl ParseEvent {
+    fn definition_kind_and_name(&self) -> Option<(&'static str, &str)> {
+        match self {
+            ParseEvent::FunctionDefinition { name, .. } => Some(("function", name)),
+            ParseEvent::MacroDefinition { name, .. } => Some(("macro", name)),
+            ParseEvent::ClassDefinition { name, .. } => Some(("class", name)),
+            ParseEvent::VariableDefinition { name, .. } => Some(("variable", name)),
+            _ => None,
+        }
+    }
+    
+    // Moves every line the event refers to, for events below an edit that added or removed lines.
+    pub(crate) fn shift_lines(&mut self, delta: isize) {
+        let lines: Vec<&mut usize> = match self {
+            ParseEvent::FunctionDefinition { start_line, end_line, .. }
+            | ParseEvent::MacroDefinition { start_line, end_line, .. }
+            | ParseEvent::ClassDefinition { start_line, end_line, .. }
+            | ParseEvent::ConditionalBlock { start_line, end_line, .. }
+            | ParseEvent::LoopBlock { start_line, end_line, .. }
+            | ParseEvent::TryBlock { start_line, end_line, .. }
+            | ParseEvent::PythonMainGuard { start_line, end_line, .. } => vec![start_line, end_line],
+            ParseEvent::VariableDefinition { line, .. }
+            | ParseEvent::ImportStatement { line, .. }
+            | ParseEvent::FunctionCall { line, .. }
+            | ParseEvent::VariableAccess { line, .. }
+            | ParseEvent::RaiseStatement { line, .. }
+            | ParseEvent::ClassInheritance { line, .. }
+            | ParseEvent::PythonDecorator { line, .. }
+            | ParseEvent::PythonAsyncFunction { line, .. }
+            | ParseEvent::PythonContextManager { line, .. }
+            | ParseEvent::PythonListComprehension { line, .. }
+            | ParseEvent::PythonTopLevelCode { line, .. }
+            | ParseEvent::PythonClassAttribute { line, .. }
+            | ParseEvent::GoMethodReceiver { line, .. }
+            | ParseEvent::DocComment { line, .. }
+            | ParseEvent::Comment { line, .. } => vec![line],
+        };
+        
+        for line in lines {
+            *line = line.saturating_add_signed(delta);
+        }
+    }
+}
+
+// A definition event together with where it lives, so it can be referred to across sessions.
+#[derive(Debug)]
+pub struct Definition<'a> {
+    pub event: &'a ParseEvent,
+    pub qualified_name: String,
+}
+
+impl Definition<'_> {
+    // FNV-1a rather than DefaultHasher so IDs stay stable across Rust releases and sessions.
+    // Lines are deliberately left out: moving a definition keeps its ID, renaming it does not.
+    pub fn definition_id(&self) -> u64 {
+        let kind = self.event.definition_kind_and_name().map_or("", |(kind, _)| kind);
+        
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in kind.bytes().chain(std::iter::once(0)).chain(self.qualified_name.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+}
+
+#[derive(Debug)]
+pub struct EventNode<'a> {
+    pub event: Option<&'a ParseEvent>,
+    pub children: Vec<EventNode<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub param_type: Option<String>,
+    pub default_value: Option<String>,
+    pub kind: ParameterKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterKind {
+    Positional,
+    PositionalOnly,
+    KeywordOnly,
+    VarPositional,
+    VarKeyword,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+    Protected,
+    // Rust `pub(crate)`.
+    Crate,
+    // Visible inside the defining module or package only: Rust `pub(super)`, Java package-private,
+    // unexported Go names, Python `_internal` names.
+    Module,
+}
+
+impl Visibility {
+    pub fn is_public(&self) -> bool {
+        *self == Visibility::Public
+    }
+}
+
+impl From<bool> for Visibility {
+    fn from(is_public: bool) -> Self {
+        if is_public { Visibility::Public } else { Visibility::Private }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum AccessType {
     Read,
     Write,
     ReadWrite,
+    Delete,
 }
 
 #[derive(Debug, Clone)]
@@ -130,42 +310,224 @@ pub enum DocType {
     Variable,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommentType {
     Line,
     Block,
     Todo,
     Fixme,
+    Custom(String),
+}
+
+// Maps marker words (`TODO`, `HACK`, ...) to the comment type they announce.
+#[derive(Debug, Clone)]
+pub struct CommentMarkers {
+    markers: Vec<(String, CommentType)>,
+}
+
+impl Default for CommentMarkers {
+    fn default() -> Self {
+        Self {
+            markers: vec![
+                ("TODO".to_string(), CommentType::Todo),
+                ("FIXME".to_string(), CommentType::Fixme),
+            ],
+        }
+    }
+}
+
+impl CommentMarkers {
+    pub fn set(&mut self, marker: &str, comment_type: CommentType) {
+        self.markers.retain(|(existing, _)| existing != marker);
+        self.markers.push((marker.to_string(), comment_type));
+    }
+
+    // Markers match whole words only, so `TODOS` or `autodo` stay plain comments.
+    pub fn classify(&self, text: &str, fallback: CommentType) -> CommentType {
+        let words: Vec<&str> = text.split(|c: char| !(c.is_alphanumeric() || c == '_')).collect();
+        self.markers.iter()
+            .find(|(marker, _)| words.contains(&marker.as_str()))
+            .map(|(_, comment_type)| comment_type.clone())
+            .unwrap_or(fallback)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Language {
+    Python,
+    Rust,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+    Other(String),
+}
+
+impl Language {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Language::Python => "python",
+            Language::Rust => "rust",
+            Language::JavaScript => "javascript",
+            Language::TypeScript => "typescript",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for Language {
+    fn from(name: &str) -> Self {
+        match name {
+            "python" => Language::Python,
+            "rust" => Language::Rust,
+            "javascript" => Language::JavaScript,
+            "typescript" => Language::TypeScript,
+            "go" => Language::Go,
+            "java" => Language::Java,
+            other => Language::Other(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub line: usize,
 }
 
 #[derive(Debug, Clone)]
 pub struct FileEvents {
     pub file_path: PathBuf,
     pub events: Vec<ParseEvent>,
-    pub language: String,
+    pub diagnostics: Vec<Diagnostic>,
+    pub cell_starts: Vec<(usize, usize)>,
+    pub line_count: usize,
+    pub language: Language,
     pub last_modified: std::time::SystemTime,
     pub parse_timestamp: std::time::SystemTime,
 }
 
 impl FileEvents {
-       pub fn new(file_path: PathBuf, language: String, last_modified: std::time::SystemTime) -> Self {
+       pub fn new(file_path: PathBuf, language: Language, last_modified: std::time::SystemTime) -> Self {
         Self {
             file_path,
             events: Vec::new(),
+            diagnostics: Vec::new(),
+            cell_starts: Vec::new(),
+            line_count: 0,
             language,
             last_modified,
             parse_timestamp: std::time::SystemTime::now(),
         }
     }
     
+    pub fn language_name(&self) -> &str {
+        self.language.as_str()
+    }
+    
+    // Notebook events keep lines in the concatenated source; this maps one back to (cell index, line in cell).
+    pub fn cell_position(&self, line: usize) -> Option<(usize, usize)> {
+        self.cell_starts.iter()
+            .rev()
+            .find(|(_, first_line)| *first_line <= line)
+            .map(|(cell_index, first_line)| (*cell_index, line - first_line + 1))
+    }
+    
     pub fn add_event(&mut self, event: ParseEvent) {
         self.events.push(event);
     }
     
+    pub fn add_diagnostic(&mut self, severity: Severity, message: String, line: usize) {
+        self.diagnostics.push(Diagnostic { severity, message, line });
+    }
+    
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    
+    // By (line, column); stable, so events starting at the same spot keep the order the parser emitted them in.
+    pub fn sort_by_position(&mut self) {
+        let mut keyed: Vec<((usize, usize), ParseEvent)> = std::mem::take(&mut self.events).into_iter()
+            .map(|event| (self.event_position(&event).unwrap_or((usize::MAX, usize::MAX)), event))
+            .collect();
+        keyed.sort_by_key(|(position, _)| *position);
+        self.events = keyed.into_iter().map(|(_, event)| event).collect();
+    }
+    
+    pub fn check_function_lengths(&mut self, max_lines: usize) {
+        let long_functions: Vec<(String, usize, usize)> = self.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, start_line, end_line, .. }
+                    if end_line - start_line + 1 > max_lines => Some((name.clone(), *start_line, *end_line)),
+                _ => None,
+            })
+            .collect();
+
+        for (name, start_line, end_line) in long_functions {
+            let message = format!(
+                "Function `{}` is too long: {} lines ({}-{}), limit is {}",
+                name, end_line - start_line + 1, start_line, end_line, max_lines
+            );
+            self.add_diagnostic(Severity::Warning, message, start_line);
+        }
+    }
+    
+    // Swaps what was parsed from old lines `start_line..=old_end_line` for `replacement`, a parse of just that
+    // stretch after an edit that moved every later line by `line_delta`. File metadata comes from `replacement`.
+    pub(crate) fn splice(&mut self, start_line: usize, old_end_line: usize, line_delta: isize, replacement: FileEvents) {
+        let in_range = |line: usize| line >= start_line && line <= old_end_line;
+        
+        // New events go where the old ones for the range were, or else right after whatever precedes the range.
+        let mut insert_at: Option<usize> = None;
+        let mut after_preceding: usize = 0;
+        for mut event in std::mem::take(&mut self.events) {
+            match self.event_line(&event) {
+                Some(line) if line > old_end_line => {
+                    event.shift_lines(line_delta);
+                    self.events.push(event);
+                }
+                Some(line) if in_range(line) => {
+                    insert_at.get_or_insert(self.events.len());
+                }
+                _ => {
+                    self.events.push(event);
+                    after_preceding = self.events.len();
+                }
+            }
+        }
+        let at = insert_at.unwrap_or(after_preceding);
+        self.events.splice(at..at, replacement.events);
+        
+        self.diagnostics.retain(|diagnostic| !in_range(diagnostic.line));
+        for diagnostic in self.diagnostics.iter_mut().filter(|diagnostic| diagnostic.line > old_end_line) {
+            diagnostic.line = diagnostic.line.saturating_add_signed(line_delta);
+        }
+        self.diagnostics.extend(replacement.diagnostics);
+        self.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        
+        self.line_count = replacement.line_count;
+        self.last_modified = replacement.last_modified;
+        self.parse_timestamp = replacement.parse_timestamp;
+    }
+    
     pub fn functions(&self) -> impl Iterator<Item = &ParseEvent> {
         self.events.iter().filter(|e| matches!(e, ParseEvent::FunctionDefinition { .. }))
     }
     
+    pub fn macros(&self) -> impl Iterator<Item = &ParseEvent> {
+        self.events.iter().filter(|e| matches!(e, ParseEvent::MacroDefinition { .. }))
+    }
+    
     pub fn classes(&self) -> impl Iterator<Item = &ParseEvent> {
         self.events.iter().filter(|e| matches!(e, ParseEvent::ClassDefinition { .. }))
     }
@@ -174,6 +536,10 @@ impl FileEvents {
         self.events.iter().filter(|e| matches!(e, ParseEvent::ImportStatement { .. }))
     }
     
+    pub fn wildcard_imports(&self) -> impl Iterator<Item = &ParseEvent> {
+        self.events.iter().filter(|e| matches!(e, ParseEvent::ImportStatement { is_wildcard: true, .. }))
+    }
+    
     pub fn variables(&self) -> impl Iterator<Item = &ParseEvent> {
         self.events.iter().filter(|e| matches!(e, ParseEvent::VariableDefinition { .. }))
     }
@@ -182,6 +548,65 @@ impl FileEvents {
         self.events.iter().filter(|e| matches!(e, ParseEvent::FunctionCall { .. }))
     }
     
+    pub fn deletions(&self) -> impl Iterator<Item = &ParseEvent> {
+        self.events.iter().filter(|e| matches!(e, ParseEvent::VariableAccess { access_type: AccessType::Delete, .. }))
+    }
+    
+    pub fn raises(&self) -> impl Iterator<Item = &ParseEvent> {
+        self.events.iter().filter(|e| matches!(e, ParseEvent::RaiseStatement { .. }))
+    }
+    
+    pub fn public_api(&self) -> Vec<&ParseEvent> {
+        let mut public_events: Vec<&ParseEvent> = self.events.iter()
+            .filter(|e| matches!(e,
+                ParseEvent::FunctionDefinition { visibility: Visibility::Public, .. }
+                | ParseEvent::ClassDefinition { visibility: Visibility::Public, .. }
+                | ParseEvent::VariableDefinition { visibility: Visibility::Public, .. }))
+            .collect();
+        
+        public_events.sort_by_key(|e| self.event_line(e));
+        public_events
+    }
+    
+    pub fn class_fields(&self, class_name: &str) -> Vec<&str> {
+        self.class_attributes(class_name, false)
+    }
+    
+    pub fn instance_fields(&self, class_name: &str) -> Vec<&str> {
+        self.class_attributes(class_name, true)
+    }
+    
+    fn class_attributes(&self, class_name: &str, instance: bool) -> Vec<&str> {
+        self.events.iter()
+            .filter_map(|e| match e {
+                ParseEvent::PythonClassAttribute { class_name: owner, attribute, is_instance, .. }
+                    if owner == class_name && *is_instance == instance => Some(attribute.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    pub fn module_doc(&self) -> Option<&str> {
+        self.events.iter().find_map(|e| match e {
+            ParseEvent::DocComment { content, doc_type: DocType::Module, .. } => Some(content.as_str()),
+            _ => None,
+        })
+    }
+
+    // The summary shown on overview pages: the docstring cut down to its first `max_lines` lines.
+    pub fn module_doc_summary(&self, max_lines: usize) -> Option<String> {
+        self.module_doc()
+            .map(|doc| doc.lines().take(max_lines).collect::<Vec<&str>>().join("\n"))
+    }
+
+    pub fn has_main_guard(&self) -> bool {
+        self.events.iter().any(|e| matches!(e, ParseEvent::PythonMainGuard { .. }))
+    }
+    
+    pub fn has_top_level_code(&self) -> bool {
+        self.events.iter().any(|e| matches!(e, ParseEvent::PythonTopLevelCode { .. }))
+    }
+    
     pub fn events_by_line(&self, line: usize) -> impl Iterator<Item = &ParseEvent> {
         self.events.iter().filter(move |e| self.event_line(e) == Some(line))
     }
@@ -196,28 +621,281 @@ impl FileEvents {
         })
     }
     
+    pub fn to_tree(&self) -> EventNode<'_> {
+        let mut spans: Vec<(usize, usize, bool, &ParseEvent)> = self.events.iter()
+            .filter_map(|e| {
+                let start_line = self.event_line(e)?;
+                match self.event_end_line(e) {
+                    Some(end_line) => Some((start_line, end_line, true, e)),
+                    None => Some((start_line, start_line, false, e)),
+                }
+            })
+            .collect();
+        
+        // Outer blocks first so they are open before anything they contain is placed.
+        spans.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        
+        let mut next: usize = 0;
+        EventNode {
+            event: None,
+            children: Self::build_children(&spans, &mut next, usize::MAX),
+        }
+    }
+    
+    fn build_children<'a>(spans: &[(usize, usize, bool, &'a ParseEvent)], next: &mut usize, parent_end: usize) -> Vec<EventNode<'a>> {
+        let mut children: Vec<EventNode<'a>> = Vec::new();
+        
+        while let Some(&(start_line, end_line, is_block, event)) = spans.get(*next) {
+            if start_line > parent_end || end_line > parent_end {
+                break;
+            }
+            
+            *next += 1;
+            let grandchildren = if is_block {
+                Self::build_children(spans, next, end_line)
+            } else {
+                Vec::new()
+            };
+            children.push(EventNode { event: Some(event), children: grandchildren });
+        }
+        
+        children
+    }
+    
+    // `root`-relative file path, then enclosing classes and functions outermost first, then the name, joined by `::`.
+    pub fn qualified_name(&self, event: &ParseEvent, root: &Path) -> Option<String> {
+        let (_, name) = event.definition_kind_and_name()?;
+        let line = self.event_line(event)?;
+        
+        let mut scopes: Vec<(usize, &str)> = self.events.iter()
+            .filter(|e| !std::ptr::eq(*e, event))
+            .filter_map(|e| match e {
+                ParseEvent::ClassDefinition { name, start_line, end_line, .. }
+                | ParseEvent::FunctionDefinition { name, start_line, end_line, .. }
+                    if *start_line <= line && line <= *end_line => Some((*start_line, name.as_str())),
+                _ => None,
+            })
+            .collect();
+        scopes.sort();
+        
+        // Joined with `/` on every platform so the same project hashes the same everywhere.
+        let file: Vec<String> = self.file_path.strip_prefix(root).unwrap_or(&self.file_path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let mut parts: Vec<String> = vec![file.join("/")];
+        parts.extend(scopes.into_iter().map(|(_, scope)| scope.to_string()));
+        parts.push(name.to_string());
+        Some(parts.join("::"))
+    }
+    
+    // Every function, macro, class and variable definition with its qualified name under `root`.
+    pub fn definitions(&self, root: &Path) -> impl Iterator<Item = Definition<'_>> {
+        self.events.iter()
+            .filter_map(move |event| Some(Definition { event, qualified_name: self.qualified_name(event, root)? }))
+    }
+    
     pub fn event_count(&self) -> usize {
         self.events.len()
     }
     
     fn event_line(&self, event: &ParseEvent) -> Option<usize> {
+        self.event_position(event).map(|(line, _)| line)
+    }
+    
+    // 1-based (line, column) where the event starts.
+    fn event_position(&self, event: &ParseEvent) -> Option<(usize, usize)> {
+        match event {
+            ParseEvent::FunctionDefinition { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::MacroDefinition { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::ClassDefinition { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::VariableDefinition { line, column, .. } => Some((*line, *column)),
+            ParseEvent::ImportStatement { line, column, .. } => Some((*line, *column)),
+            ParseEvent::ConditionalBlock { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::LoopBlock { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::TryBlock { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::FunctionCall { line, column, .. } => Some((*line, *column)),
+            ParseEvent::VariableAccess { line, column, .. } => Some((*line, *column)),
+            ParseEvent::RaiseStatement { line, column, .. } => Some((*line, *column)),
+            ParseEvent::ClassInheritance { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonDecorator { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonAsyncFunction { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonContextManager { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonListComprehension { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonMainGuard { start_line, start_column, .. } => Some((*start_line, *start_column)),
+            ParseEvent::PythonTopLevelCode { line, column, .. } => Some((*line, *column)),
+            ParseEvent::PythonClassAttribute { line, column, .. } => Some((*line, *column)),
+            ParseEvent::GoMethodReceiver { line, column, .. } => Some((*line, *column)),
+            ParseEvent::DocComment { line, column, .. } => Some((*line, *column)),
+            ParseEvent::Comment { line, column, .. } => Some((*line, *column)),
+        }
+    }
+    
+    fn event_end_line(&self, event: &ParseEvent) -> Option<usize> {
         match event {
-            ParseEvent::FunctionDefinition { start_line, .. } => Some(*start_line),
-            ParseEvent::ClassDefinition { start_line, .. } => Some(*start_line),
-            ParseEvent::VariableDefinition { line, .. } => Some(*line),
-            ParseEvent::ImportStatement { line, .. } => Some(*line),
-            ParseEvent::ConditionalBlock { start_line, .. } => Some(*start_line),
-            ParseEvent::LoopBlock { start_line, .. } => Some(*start_line),
-            ParseEvent::TryBlock { start_line, .. } => Some(*start_line),
-            ParseEvent::FunctionCall { line, .. } => Some(*line),
-            ParseEvent::VariableAccess { line, .. } => Some(*line),
-            ParseEvent::ClassInheritance { line, .. } => Some(*line),
-            ParseEvent::PythonDecorator { line, .. } => Some(*line),
-            ParseEvent::PythonAsyncFunction { line, .. } => Some(*line),
-            ParseEvent::PythonContextManager { line, .. } => Some(*line),
-            ParseEvent::PythonListComprehension { line, .. } => Some(*line),
-            ParseEvent::DocComment { line, .. } => Some(*line),
-            ParseEvent::Comment { line, .. } => Some(*line),
+            ParseEvent::FunctionDefinition { end_line, .. }
+            | ParseEvent::MacroDefinition { end_line, .. }
+            | ParseEvent::ClassDefinition { end_line, .. }
+            | ParseEvent::ConditionalBlock { end_line, .. }
+            | ParseEvent::LoopBlock { end_line, .. }
+            | ParseEvent::TryBlock { end_line, .. }
+            | ParseEvent::PythonMainGuard { end_line, .. } => Some(*end_line),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileSummary {
+    pub path: PathBuf,
+    pub language: Language,
+    pub function_count: usize,
+    pub class_count: usize,
+    pub line_count: usize,
+}
+
+impl From<&FileEvents> for FileSummary {
+    fn from(file_events: &FileEvents) -> Self {
+        Self {
+            path: file_events.file_path.clone(),
+            language: file_events.language.clone(),
+            function_count: file_events.functions().count(),
+            class_count: file_events.classes().count(),
+            line_count: file_events.line_count,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{python::PythonParser, r#trait::LanguageParser};
+
+    fn parse_at(root: &Path, name: &str, source: &str) -> FileEvents {
+        let path = root.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        PythonParser::new().parse_file(source, &path).unwrap()
+    }
+
+    fn method_id(file_events: &FileEvents, root: &Path, method: &str) -> u64 {
+        file_events.definitions(root)
+            .find(|definition| matches!(definition.event, ParseEvent::FunctionDefinition { name, .. } if name == method))
+            .unwrap()
+            .definition_id()
+    }
+
+    #[test]
+    fn definition_id_survives_a_move_but_not_a_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let original = parse_at(root, "pkg/cache.py", "class Cache:\n    def get(self):\n        pass\n");
+        let moved = parse_at(root, "pkg/cache.py", "import os\n\n\nclass Cache:\n    def get(self):\n        pass\n");
+        let renamed = parse_at(root, "pkg/cache.py", "class Cache:\n    def fetch(self):\n        pass\n");
+
+        let id = method_id(&original, root, "get");
+
+        assert_eq!(original.qualified_name(original.functions().next().unwrap(), root).as_deref(), Some("pkg/cache.py::Cache::get"));
+        assert_eq!(method_id(&moved, root, "get"), id);
+        assert_ne!(method_id(&renamed, root, "fetch"), id);
+    }
+
+    #[test]
+    fn definition_id_tells_apart_same_names_in_other_scopes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let cache = parse_at(root, "cache.py", "class Cache:\n    def get(self):\n        pass\n\nclass Store:\n    def get(self):\n        pass\n");
+        let other_file = parse_at(root, "other.py", "class Cache:\n    def get(self):\n        pass\n");
+
+        let ids: Vec<u64> = cache.definitions(root)
+            .filter(|definition| matches!(definition.event, ParseEvent::FunctionDefinition { .. }))
+            .map(|definition| definition.definition_id())
+            .collect();
+
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(method_id(&other_file, root, "get"), ids[0]);
+    }
+
+    #[test]
+    fn definition_id_tells_apart_helpers_nested_in_different_functions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let jobs = parse_at(root, "jobs.py", "def load():\n    def helper():\n        pass\n\ndef save():\n    def helper():\n        pass\n");
+
+        let helpers: Vec<Definition> = jobs.definitions(root)
+            .filter(|definition| matches!(definition.event, ParseEvent::FunctionDefinition { name, .. } if name == "helper"))
+            .collect();
+
+        let names: Vec<&str> = helpers.iter().map(|definition| definition.qualified_name.as_str()).collect();
+        assert_eq!(names, vec!["jobs.py::load::helper", "jobs.py::save::helper"]);
+        assert_ne!(helpers[0].definition_id(), helpers[1].definition_id());
+    }
+
+    #[test]
+    fn tree_nests_a_method_under_its_class() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_events = parse_at(dir.path(), "shapes.py", "class Square:\n    def area(self):\n        return 4\n\ndef main():\n    pass\n");
+
+        let tree = file_events.to_tree();
+        let class = tree.children.iter()
+            .find(|node| matches!(node.event, Some(ParseEvent::ClassDefinition { name, .. }) if name == "Square"))
+            .unwrap();
+
+        assert!(class.children.iter().any(|node| matches!(node.event, Some(ParseEvent::FunctionDefinition { name, .. }) if name == "area")));
+        assert!(tree.children.iter().any(|node| matches!(node.event, Some(ParseEvent::FunctionDefinition { name, .. }) if name == "main")));
+    }
+
+    #[test]
+    fn long_function_gets_a_diagnostic() {
+        let dir = tempfile::tempdir().unwrap();
+        let body: String = (0..149).map(|i| format!("    x{i} = {i}\n")).collect();
+        let source = format!("def long():\n{body}\ndef short():\n    pass\n");
+        let mut file_events = parse_at(dir.path(), "long.py", &source);
+
+        file_events.check_function_lengths(100);
+
+        assert_eq!(file_events.diagnostics.len(), 1);
+        assert_eq!(file_events.diagnostics[0].severity, Severity::Warning);
+        assert_eq!(file_events.diagnostics[0].line, 1);
+        assert_eq!(file_events.diagnostics[0].message, "Function `long` is too long: 150 lines (1-150), limit is 100");
+    }
+
+    #[test]
+    fn sort_by_position_orders_events_by_line() {
+        let dir = tempfile::tempdir().unwrap();
+        // The main guard is emitted with the module, ahead of the function defined above it.
+        let mut file_events = parse_at(dir.path(), "app.py", "def main():\n    run()\n\nif __name__ == \"__main__\":\n    main()\n");
+        let lines = |file_events: &FileEvents| -> Vec<usize> {
+            file_events.events.iter().map(|event| file_events.event_line(event).unwrap()).collect()
+        };
+        assert!(!lines(&file_events).is_sorted());
+
+        file_events.sort_by_position();
+
+        assert!(lines(&file_events).is_sorted());
+        assert!(matches!(file_events.events[0], ParseEvent::FunctionDefinition { .. }));
+    }
+
+    #[test]
+    fn sort_by_position_orders_events_on_one_line_by_column() {
+        let dir = tempfile::tempdir().unwrap();
+        // The comprehension is emitted at its list, ahead of the call nested inside it, but starts at its `for` clause.
+        let mut file_events = parse_at(dir.path(), "app.py", "squares = [square(n) for n in values]\n");
+
+        file_events.sort_by_position();
+
+        let kinds: Vec<String> = file_events.events.iter()
+            .map(|event| {
+                let (line, column) = file_events.event_position(event).unwrap();
+                let kind = format!("{:?}", event);
+                format!("{} @ {}:{}", &kind[..kind.find(' ').unwrap()], line, column)
+            })
+            .collect();
+        assert_eq!(kinds, vec![
+            "VariableDefinition @ 1:1".to_string(),
+            "FunctionCall @ 1:12".to_string(),
+            "PythonListComprehension @ 1:22".to_string(),
+        ]);
+    }
+}
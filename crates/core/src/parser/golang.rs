@@ -0,0 +1,350 @@
+use tree_sitter::{Language, Node, TreeCursor};
+use tree_sitter_go::language as go_language;
+
+use crate::parser::{event::{self, FileEvents, Parameter, ParameterKind, ParseEvent, Visibility}, r#trait::LanguageParser};
+
+#[derive(Default)]
+pub struct GoParser {
+    record_snippets: bool,
+}
+
+impl LanguageParser for GoParser {
+    fn language(&self) -> Language {
+        go_language()
+    }
+
+    fn language_name(&self) -> &'static str {
+        "go"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::Go
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["go"]
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+    }
+
+    // Go exports exactly the identifiers that start with an uppercase letter; the rest stay in the package.
+    fn symbol_visibility(&self, _node: &Node, name: &str, _source_code: &str) -> Visibility {
+        if name.starts_with(|c: char| c.is_uppercase()) {
+            Visibility::Public
+        } else {
+            Visibility::Module
+        }
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.walk_scoped(node, source_code, file_events, &mut Vec::new())
+    }
+}
+
+impl GoParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.record_snippets = enabled;
+        self
+    }
+
+    // `scope` holds the names of the enclosing functions, innermost last, so calls can name their caller.
+    fn walk_scoped(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let should_parse_children: bool = self.parse_node(node, source_code, file_events, scope)?;
+
+        if should_parse_children {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.walk_scoped(&child, source_code, file_events, scope)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<bool, Box<dyn std::error::Error>> {
+        match node.kind() {
+            "function_declaration" | "method_declaration" => {
+                let Some(function_event) = self.parse_function(node, source_code) else {
+                    return Ok(false);
+                };
+                let ParseEvent::FunctionDefinition { name, .. } = &function_event else {
+                    return Ok(false);
+                };
+                let name = name.clone();
+                file_events.add_event(function_event);
+
+                if let Some(receiver_event) = self.parse_receiver(node, &name, source_code) {
+                    file_events.add_event(receiver_event);
+                }
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    scope.push(name);
+                    self.walk_scoped(&body, source_code, file_events, scope)?;
+                    scope.pop();
+                }
+
+                Ok(false)
+            }
+            "type_spec" => {
+                if let Some(class_event) = self.parse_struct(node, source_code) {
+                    file_events.add_event(class_event);
+                }
+                Ok(false)
+            }
+            "import_spec" => {
+                if let Some(import_event) = self.parse_import(node, source_code) {
+                    file_events.add_event(import_event);
+                }
+                Ok(false)
+            }
+            "call_expression" => {
+                if let Some(call_event) = self.parse_call(node, source_code, scope) {
+                    file_events.add_event(call_event);
+                }
+                Ok(true)
+            }
+            "comment" => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    fn parse_function(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+        let (parameters, parameters_detailed) = node.child_by_field_name("parameters")
+            .map(|params_node| self.extract_parameters(&params_node, source_code))
+            .unwrap_or_default();
+        let return_type = node.child_by_field_name("result")
+            .map(|result| self.node_text(result, source_code).to_string());
+
+        Some(ParseEvent::FunctionDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            parameters,
+            parameters_detailed,
+            return_type,
+            is_generator: false,
+            is_async_generator: false,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // `func (p *Point) Move()` belongs to `Point`; generic receivers (`*Stack[T]`) drop their type arguments.
+    fn parse_receiver(&self, node: &Node, method: &str, source_code: &str) -> Option<ParseEvent> {
+        let receiver = node.child_by_field_name("receiver")?;
+        let mut cursor: TreeCursor = receiver.walk();
+        let receiver_type = receiver.named_children(&mut cursor)
+            .find(|child| child.kind() == "parameter_declaration")?
+            .child_by_field_name("type")?;
+
+        let type_text = self.node_text(receiver_type, source_code);
+        let is_pointer = type_text.starts_with('*');
+        let receiver_type = type_text.trim_start_matches('*').trim();
+        let receiver_type = receiver_type.split('[').next().unwrap_or(receiver_type);
+
+        Some(ParseEvent::GoMethodReceiver {
+            method: method.to_string(),
+            receiver_type: receiver_type.to_string(),
+            is_pointer,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        })
+    }
+
+    // Only `type X struct {...}` is a class; other type specs (interfaces, aliases) are skipped.
+    // Embedded fields have no name and are reported by their type.
+    fn parse_struct(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+        let struct_type = node.child_by_field_name("type").filter(|type_node| type_node.kind() == "struct_type")?;
+
+        let mut fields: Vec<String> = Vec::new();
+        let mut cursor: TreeCursor = struct_type.walk();
+        for field_list in struct_type.named_children(&mut cursor).filter(|child| child.kind() == "field_declaration_list") {
+            let mut list_cursor: TreeCursor = field_list.walk();
+            for declaration in field_list.named_children(&mut list_cursor).filter(|child| child.kind() == "field_declaration") {
+                let mut name_cursor: TreeCursor = declaration.walk();
+                let names: Vec<Node> = declaration.children_by_field_name("name", &mut name_cursor).collect();
+                if names.is_empty() {
+                    if let Some(embedded) = declaration.child_by_field_name("type") {
+                        fields.push(self.node_text(embedded, source_code).trim_start_matches('*').to_string());
+                    }
+                } else {
+                    fields.extend(names.iter().map(|field| self.node_text(*field, source_code).to_string()));
+                }
+            }
+        }
+
+        // The span covers the whole declaration when it is the only spec in `type X struct {...}`.
+        let definition = node.parent().filter(|parent| parent.kind() == "type_declaration" && parent.named_child_count() == 1).unwrap_or(*node);
+
+        Some(ParseEvent::ClassDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: definition.start_position().row + 1,
+            start_column: definition.start_position().column + 1,
+            end_line: definition.end_position().row + 1,
+            fields,
+            snippet: self.snippet(&definition, source_code),
+        })
+    }
+
+    // Each spec of a grouped `import (...)` block becomes its own import; a dot import is a wildcard.
+    fn parse_import(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let path = node.child_by_field_name("path")?;
+        let module = self.node_text(path, source_code).trim_matches(|c| c == '"' || c == '`').to_string();
+        let is_wildcard = node.child_by_field_name("name").is_some_and(|name| name.kind() == "dot");
+
+        Some(ParseEvent::ImportStatement {
+            module,
+            items: Vec::new(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            is_wildcard,
+        })
+    }
+
+    fn parse_call(&self, node: &Node, source_code: &str, scope: &[String]) -> Option<ParseEvent> {
+        let callee = node.child_by_field_name("function")?;
+
+        let mut arguments: Vec<String> = Vec::new();
+        if let Some(args_node) = node.child_by_field_name("arguments") {
+            let mut cursor: TreeCursor = args_node.walk();
+            for argument in args_node.named_children(&mut cursor) {
+                if argument.kind() != "comment" {
+                    arguments.push(self.node_text(argument, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::FunctionCall {
+            caller_function: scope.last().cloned(),
+            callee: self.node_text(callee, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            arguments,
+        })
+    }
+
+    //Helper functions
+
+    // `a, b int` declares two parameters sharing one type.
+    fn extract_parameters(&self, params_node: &Node, source_code: &str) -> (Vec<String>, Vec<Parameter>) {
+        let mut parameters: Vec<String> = Vec::new();
+        let mut parameters_detailed: Vec<Parameter> = Vec::new();
+        let mut cursor: TreeCursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let kind = match child.kind() {
+                "parameter_declaration" => ParameterKind::Positional,
+                "variadic_parameter_declaration" => ParameterKind::VarPositional,
+                _ => continue,
+            };
+
+            parameters.push(self.node_text(child, source_code).to_string());
+
+            let param_type = child.child_by_field_name("type")
+                .map(|param_type| self.node_text(param_type, source_code).to_string());
+            let mut name_cursor: TreeCursor = child.walk();
+            for name in child.children_by_field_name("name", &mut name_cursor) {
+                parameters_detailed.push(Parameter {
+                    name: self.node_text(name, source_code).to_string(),
+                    param_type: param_type.clone(),
+                    default_value: None,
+                    kind,
+                });
+            }
+        }
+
+        (parameters, parameters_detailed)
+    }
+
+    fn snippet(&self, node: &Node, source_code: &str) -> Option<String> {
+        self.record_snippets.then(|| self.node_text(*node, source_code).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(".go").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        GoParser::new().parse_file(source, file.path()).unwrap()
+    }
+
+    #[test]
+    fn methods_record_their_receiver() {
+        let file_events = parse("package shapes\n\nfunc (p *Point) Move(dx, dy int) {}\n\nfunc (s Stack[T]) Len() int { return 0 }\n\nfunc Free() {}\n");
+
+        let receivers: Vec<(&str, &str, bool)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::GoMethodReceiver { method, receiver_type, is_pointer, .. } => Some((method.as_str(), receiver_type.as_str(), *is_pointer)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(receivers, vec![("Move", "Point", true), ("Len", "Stack", false)]);
+
+        let move_parameters: Vec<(String, Option<String>)> = file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, .. } if name == "Move" => {
+                    Some(parameters_detailed.iter().map(|parameter| (parameter.name.clone(), parameter.param_type.clone())).collect())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(move_parameters, vec![("dx".to_string(), Some("int".to_string())), ("dy".to_string(), Some("int".to_string()))]);
+    }
+
+    #[test]
+    fn grouped_imports_and_dot_import() {
+        let file_events = parse("package main\n\nimport (\n\t\"fmt\"\n\tstr \"strings\"\n\t. \"math\"\n)\n\nimport \"os\"\n");
+
+        let imports: Vec<(&str, bool, usize)> = file_events.imports()
+            .filter_map(|event| match event {
+                ParseEvent::ImportStatement { module, is_wildcard, line, .. } => Some((module.as_str(), *is_wildcard, *line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(imports, vec![("fmt", false, 4), ("strings", false, 5), ("math", true, 6), ("os", false, 9)]);
+    }
+
+    #[test]
+    fn structs_are_classes_with_named_and_embedded_fields() {
+        let file_events = parse("package shapes\n\ntype Point struct {\n\tX, Y int\n\t*Base\n}\n\ntype Mover interface {\n\tMove()\n}\n");
+
+        let classes: Vec<(&str, Vec<&str>, usize, usize)> = file_events.classes()
+            .filter_map(|event| match event {
+                ParseEvent::ClassDefinition { name, fields, start_line, end_line, .. } => Some((name.as_str(), fields.iter().map(String::as_str).collect(), *start_line, *end_line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(classes, vec![("Point", vec!["X", "Y", "Base"], 3, 6)]);
+    }
+
+    #[test]
+    fn capitalized_names_are_exported() {
+        let file_events = parse("package shapes\n\nfunc Area() {}\n\nfunc perimeter() {}\n\ntype Point struct{}\n\ntype point struct{}\n");
+
+        let visibilities: Vec<(&str, Visibility)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, visibility, .. } | ParseEvent::ClassDefinition { name, visibility, .. } => Some((name.as_str(), *visibility)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(visibilities, vec![
+            ("Area", Visibility::Public),
+            ("perimeter", Visibility::Module),
+            ("Point", Visibility::Public),
+            ("point", Visibility::Module),
+        ]);
+    }
+}
@@ -0,0 +1,139 @@
+use std::path::Path;
+use serde_json::Value;
+use tree_sitter::{Language, Node};
+use tree_sitter_python::language as python_language;
+
+use crate::parser::{event::{self, CommentType, FileEvents}, python::PythonParser, r#trait::{IncrementalParse, LanguageParser, PreviousParse}};
+
+// The joined Python source and a (cell index, first line) pair per code cell.
+type CodeCells = (String, Vec<(usize, usize)>);
+
+#[derive(Default)]
+pub struct IpynbParser {
+    python: PythonParser,
+}
+
+impl IpynbParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Code cells are joined into one Python source; returns it with (cell index, first line) per code cell.
+    fn extract_code_cells(&self, notebook: &Value) -> Result<CodeCells, Box<dyn std::error::Error>> {
+        let cells = notebook.get("cells")
+            .and_then(Value::as_array)
+            .ok_or("Notebook has no cells array")?;
+
+        let mut source = String::new();
+        let mut cell_starts: Vec<(usize, usize)> = Vec::new();
+        let mut next_line: usize = 1;
+
+        for (cell_index, cell) in cells.iter().enumerate() {
+            if cell.get("cell_type").and_then(Value::as_str) != Some("code") {
+                continue;
+            }
+
+            let mut cell_source = match cell.get("source") {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+                _ => String::new(),
+            };
+            if !cell_source.ends_with('\n') {
+                cell_source.push('\n');
+            }
+
+            cell_starts.push((cell_index, next_line));
+            next_line += cell_source.matches('\n').count();
+            source.push_str(&cell_source);
+        }
+
+        Ok((source, cell_starts))
+    }
+}
+
+impl LanguageParser for IpynbParser {
+    fn language(&self) -> Language {
+        python_language()
+    }
+
+    fn language_name(&self) -> &'static str {
+        "ipynb"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::Python
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["ipynb"]
+    }
+
+    fn parse_file(&self, content: &str, file_path: &Path) -> Result<FileEvents, Box<dyn std::error::Error>> {
+        let notebook: Value = serde_json::from_str(content)?;
+        let (source, cell_starts) = self.extract_code_cells(&notebook)?;
+
+        let mut file_events = self.python.parse_file(&source, file_path)?;
+        file_events.cell_starts = cell_starts;
+        Ok(file_events)
+    }
+
+    // The tree covers the extracted cells, not the notebook JSON, so it can't be edited from a diff of the file.
+    fn parse_file_incremental(&self, content: &str, file_path: &Path, _previous: Option<PreviousParse>) -> Result<IncrementalParse, Box<dyn std::error::Error>> {
+        Ok((self.parse_file(content, file_path)?, None))
+    }
+
+    // Notebook JSON has to be decoded whole before any Python source exists.
+    fn parse_streamed(&self, content: &[u8], file_path: &Path, _chunk_size: usize) -> Result<FileEvents, Box<dyn std::error::Error>> {
+        self.parse_file(std::str::from_utf8(content)?, file_path)
+    }
+
+    // Malformed notebook JSON counts as a file that doesn't parse cleanly.
+    fn validate(&self, content: &str, file_path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        let Ok(notebook) = serde_json::from_str::<Value>(content) else {
+            return Ok(false);
+        };
+        let (source, _) = self.extract_code_cells(&notebook)?;
+        self.python.validate(&source, file_path)
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.python.set_record_snippets(enabled);
+    }
+
+    fn set_comment_marker(&mut self, marker: &str, comment_type: CommentType) {
+        self.python.set_comment_marker(marker, comment_type);
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.python.walk_tree(node, source_code, file_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::event::ParseEvent;
+
+    #[test]
+    fn functions_are_attributed_to_their_cells() {
+        let notebook = r##"{
+            "cells": [
+                {"cell_type": "code", "source": ["import os\n", "\n", "def load():\n", "    pass\n"]},
+                {"cell_type": "markdown", "source": ["# Analysis"]},
+                {"cell_type": "code", "source": "def plot():\n    pass"}
+            ]
+        }"##;
+        let file = tempfile::Builder::new().suffix(".ipynb").tempfile().unwrap();
+        std::fs::write(file.path(), notebook).unwrap();
+
+        let file_events = IpynbParser::new().parse_file(notebook, file.path()).unwrap();
+        let positions: Vec<(&str, Option<(usize, usize)>)> = file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, start_line, .. } => Some((name.as_str(), file_events.cell_position(*start_line))),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(positions, vec![("load", Some((0, 3))), ("plot", Some((2, 1)))]);
+    }
+}
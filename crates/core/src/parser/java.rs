@@ -0,0 +1,413 @@
+use tree_sitter::{Language, Node, TreeCursor};
+use tree_sitter_java::language as java_language;
+
+use crate::parser::{event::{self, FileEvents, Parameter, ParameterKind, ParseEvent, Visibility}, r#trait::LanguageParser};
+
+#[derive(Default)]
+pub struct JavaParser {
+    record_snippets: bool,
+}
+
+impl LanguageParser for JavaParser {
+    fn language(&self) -> Language {
+        java_language()
+    }
+
+    fn language_name(&self) -> &'static str {
+        "java"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::Java
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["java"]
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+    }
+
+    // Visibility comes from the access modifier; without one, interface members are public and
+    // everything else is package-private.
+    fn symbol_visibility(&self, node: &Node, _name: &str, source_code: &str) -> Visibility {
+        let mut cursor: TreeCursor = node.walk();
+        let modifiers: Vec<&str> = node.children(&mut cursor)
+            .filter(|child| child.kind() == "modifiers")
+            .flat_map(|modifiers| self.node_text(modifiers, source_code).split_whitespace())
+            .collect();
+
+        if modifiers.contains(&"public") {
+            Visibility::Public
+        } else if modifiers.contains(&"protected") {
+            Visibility::Protected
+        } else if modifiers.contains(&"private") {
+            Visibility::Private
+        } else if node.parent().is_some_and(|parent| parent.kind() == "interface_body") {
+            Visibility::Public
+        } else {
+            Visibility::Module
+        }
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.walk_scoped(node, source_code, file_events, &mut Vec::new())
+    }
+}
+
+impl JavaParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.record_snippets = enabled;
+        self
+    }
+
+    // `scope` holds the names of the enclosing methods, innermost last, so calls can name their caller.
+    fn walk_scoped(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let should_parse_children: bool = self.parse_node(node, source_code, file_events, scope)?;
+
+        if should_parse_children {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.walk_scoped(&child, source_code, file_events, scope)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<bool, Box<dyn std::error::Error>> {
+        match node.kind() {
+            "method_declaration" | "constructor_declaration" => {
+                let Some(function_event) = self.parse_method(node, source_code) else {
+                    return Ok(false);
+                };
+                let ParseEvent::FunctionDefinition { name, .. } = &function_event else {
+                    return Ok(false);
+                };
+                let name = name.clone();
+                file_events.add_event(function_event);
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    scope.push(name);
+                    self.walk_scoped(&body, source_code, file_events, scope)?;
+                    scope.pop();
+                }
+
+                Ok(false)
+            }
+            "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration" => {
+                if let Some(class_event) = self.parse_class(node, source_code) {
+                    file_events.add_event(class_event);
+                }
+                if let Some(inheritance_event) = self.parse_inheritance(node, source_code) {
+                    file_events.add_event(inheritance_event);
+                }
+                Ok(true)
+            }
+            "import_declaration" => {
+                if let Some(import_event) = self.parse_import(node, source_code) {
+                    file_events.add_event(import_event);
+                }
+                Ok(false)
+            }
+            "method_invocation" => {
+                if let Some(call_event) = self.parse_call(node, source_code, scope) {
+                    file_events.add_event(call_event);
+                }
+                Ok(true)
+            }
+            "line_comment" | "block_comment" => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    fn parse_method(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+        let (parameters, parameters_detailed) = node.child_by_field_name("parameters")
+            .map(|params_node| self.extract_parameters(&params_node, source_code))
+            .unwrap_or_default();
+        let return_type = node.child_by_field_name("type")
+            .map(|return_type| self.node_text(return_type, source_code).to_string());
+
+        Some(ParseEvent::FunctionDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            parameters,
+            parameters_detailed,
+            return_type,
+            is_generator: false,
+            is_async_generator: false,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // Enum constants and record components are reported as fields alongside the declared ones.
+    fn parse_class(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(components) = node.child_by_field_name("parameters") {
+            let mut cursor: TreeCursor = components.walk();
+            for component in components.named_children(&mut cursor) {
+                if let Some(component_name) = component.child_by_field_name("name") {
+                    fields.push(self.node_text(component_name, source_code).to_string());
+                }
+            }
+        }
+
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor: TreeCursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                match member.kind() {
+                    "enum_constant" => fields.extend(member.child_by_field_name("name")
+                        .map(|constant| self.node_text(constant, source_code).to_string())),
+                    "field_declaration" | "constant_declaration" => {
+                        let mut declarator_cursor: TreeCursor = member.walk();
+                        for declarator in member.children_by_field_name("declarator", &mut declarator_cursor) {
+                            fields.extend(declarator.child_by_field_name("name")
+                                .map(|field| self.node_text(field, source_code).to_string()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Some(ParseEvent::ClassDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            fields,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // `extends` and `implements` both count as parents; for interfaces `extends` lists several.
+    fn parse_inheritance(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let child_class = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+
+        let mut parent_classes: Vec<String> = Vec::new();
+        let mut cursor: TreeCursor = node.walk();
+        for clause in node.named_children(&mut cursor) {
+            if !matches!(clause.kind(), "superclass" | "super_interfaces" | "extends_interfaces") {
+                continue;
+            }
+
+            let mut clause_cursor: TreeCursor = clause.walk();
+            for parent in clause.named_children(&mut clause_cursor) {
+                if parent.kind() == "type_list" {
+                    let mut list_cursor: TreeCursor = parent.walk();
+                    parent_classes.extend(parent.named_children(&mut list_cursor)
+                        .map(|parent_type| self.node_text(parent_type, source_code).to_string()));
+                } else {
+                    parent_classes.push(self.node_text(parent, source_code).to_string());
+                }
+            }
+        }
+
+        if parent_classes.is_empty() {
+            return None;
+        }
+
+        Some(ParseEvent::ClassInheritance {
+            child_class,
+            parent_classes,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        })
+    }
+
+    // `import java.util.List;` imports `List` from `java.util`; `import java.util.*;` is a wildcard.
+    fn parse_import(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let mut cursor: TreeCursor = node.walk();
+        let path = node.named_children(&mut cursor)
+            .find(|child| matches!(child.kind(), "scoped_identifier" | "identifier"))?;
+        let is_wildcard = node.named_children(&mut cursor).any(|child| child.kind() == "asterisk");
+
+        let (module, items) = match (is_wildcard, path.child_by_field_name("scope"), path.child_by_field_name("name")) {
+            (false, Some(scope), Some(name)) => (
+                self.node_text(scope, source_code).to_string(),
+                vec![self.node_text(name, source_code).to_string()],
+            ),
+            _ => (self.node_text(path, source_code).to_string(), Vec::new()),
+        };
+
+        Some(ParseEvent::ImportStatement {
+            module,
+            items,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            is_wildcard,
+        })
+    }
+
+    fn parse_call(&self, node: &Node, source_code: &str, scope: &[String]) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code);
+        let callee = match node.child_by_field_name("object") {
+            Some(object) => format!("{}.{}", self.node_text(object, source_code), name),
+            None => name.to_string(),
+        };
+
+        let mut arguments: Vec<String> = Vec::new();
+        if let Some(args_node) = node.child_by_field_name("arguments") {
+            let mut cursor: TreeCursor = args_node.walk();
+            for argument in args_node.named_children(&mut cursor) {
+                if !matches!(argument.kind(), "line_comment" | "block_comment") {
+                    arguments.push(self.node_text(argument, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::FunctionCall {
+            caller_function: scope.last().cloned(),
+            callee,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            arguments,
+        })
+    }
+
+    //Helper functions
+
+    fn extract_parameters(&self, params_node: &Node, source_code: &str) -> (Vec<String>, Vec<Parameter>) {
+        let mut parameters: Vec<String> = Vec::new();
+        let mut parameters_detailed: Vec<Parameter> = Vec::new();
+        let mut cursor: TreeCursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            let (name, param_type, kind) = match child.kind() {
+                "formal_parameter" => (
+                    child.child_by_field_name("name"),
+                    child.child_by_field_name("type"),
+                    ParameterKind::Positional,
+                ),
+                // `String... rest` has no fields: a type node followed by the declarator.
+                "spread_parameter" => {
+                    let mut spread_cursor: TreeCursor = child.walk();
+                    let parts: Vec<Node> = child.named_children(&mut spread_cursor).collect();
+                    (
+                        parts.iter().find(|part| part.kind() == "variable_declarator")
+                            .and_then(|declarator| declarator.child_by_field_name("name")),
+                        parts.iter().find(|part| !matches!(part.kind(), "modifiers" | "variable_declarator")).copied(),
+                        ParameterKind::VarPositional,
+                    )
+                }
+                _ => continue,
+            };
+
+            parameters.push(self.node_text(child, source_code).to_string());
+            if let Some(name) = name {
+                parameters_detailed.push(Parameter {
+                    name: self.node_text(name, source_code).to_string(),
+                    param_type: param_type.map(|param_type| self.node_text(param_type, source_code).to_string()),
+                    default_value: None,
+                    kind,
+                });
+            }
+        }
+
+        (parameters, parameters_detailed)
+    }
+
+    fn snippet(&self, node: &Node, source_code: &str) -> Option<String> {
+        self.record_snippets.then(|| self.node_text(*node, source_code).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(".java").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        JavaParser::new().parse_file(source, file.path()).unwrap()
+    }
+
+    fn parents(file_events: &FileEvents) -> Vec<(&str, Vec<&str>)> {
+        file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::ClassInheritance { child_class, parent_classes, .. } => {
+                    Some((child_class.as_str(), parent_classes.iter().map(String::as_str).collect()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn superclass_and_interfaces_are_all_parents() {
+        let file_events = parse("class Dog extends Animal implements Pet, Comparable<Dog> {}\n\ninterface Pet extends Named, Fed {}\n\nclass Plain {}\n");
+
+        assert_eq!(parents(&file_events), vec![
+            ("Dog", vec!["Animal", "Pet", "Comparable<Dog>"]),
+            ("Pet", vec!["Named", "Fed"]),
+        ]);
+    }
+
+    #[test]
+    fn single_name_and_wildcard_imports() {
+        let file_events = parse("import java.util.List;\nimport java.io.*;\n\nclass A {}\n");
+
+        let imports: Vec<String> = file_events.imports()
+            .filter_map(|event| match event {
+                ParseEvent::ImportStatement { module, items, is_wildcard, .. } => Some(format!("{} {:?} wildcard={}", module, items, is_wildcard)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(imports, vec![
+            "java.util [\"List\"] wildcard=false".to_string(),
+            "java.io [] wildcard=true".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn varargs_are_var_positional_parameters() {
+        let file_events = parse("class Log {\n    void print(String format, Object... args) {}\n}\n");
+
+        let parameters: Vec<String> = file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, .. } if name == "print" => Some(parameters_detailed.iter()
+                    .map(|parameter| format!("{}: {:?} {:?}", parameter.name, parameter.param_type, parameter.kind))
+                    .collect()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(parameters, vec![
+            "format: Some(\"String\") Positional".to_string(),
+            "args: Some(\"Object\") VarPositional".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn visibility_follows_modifiers_and_interface_members_are_public() {
+        let file_events = parse("public class Shop {\n    public void open() {}\n    protected void stock() {}\n    private void count() {}\n    void restock() {}\n}\n\ninterface Store {\n    void sell();\n}\n");
+
+        let mut visibility: Vec<(String, Visibility)> = file_events.functions().chain(file_events.classes())
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, visibility, .. } | ParseEvent::ClassDefinition { name, visibility, .. } => Some((name.clone(), *visibility)),
+                _ => None,
+            })
+            .collect();
+        visibility.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(visibility, vec![
+            ("Shop".to_string(), Visibility::Public),
+            ("Store".to_string(), Visibility::Module),
+            ("count".to_string(), Visibility::Private),
+            ("open".to_string(), Visibility::Public),
+            ("restock".to_string(), Visibility::Module),
+            ("sell".to_string(), Visibility::Public),
+            ("stock".to_string(), Visibility::Protected),
+        ]);
+    }
+}
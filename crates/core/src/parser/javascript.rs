@@ -0,0 +1,523 @@
+use tree_sitter::{Language, Node, TreeCursor};
+use tree_sitter_javascript::language as javascript_language;
+
+use crate::parser::{event::{self, FileEvents, Parameter, ParameterKind, ParseEvent, Visibility}, jsx, r#trait::LanguageParser};
+
+#[derive(Default)]
+pub struct JavaScriptParser {
+    record_snippets: bool,
+}
+
+impl LanguageParser for JavaScriptParser {
+    fn language(&self) -> Language {
+        javascript_language()
+    }
+
+    fn language_name(&self) -> &'static str {
+        "javascript"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::JavaScript
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["js", "mjs", "cjs", "jsx"]
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+    }
+
+    // Methods are public unless `#private` or restricted by a TypeScript modifier; everything else
+    // is public only when exported.
+    fn symbol_visibility(&self, node: &Node, name: &str, source_code: &str) -> Visibility {
+        if !matches!(node.kind(), "method_definition" | "abstract_method_signature") {
+            return if self.is_exported(node) { Visibility::Public } else { Visibility::Module };
+        }
+
+        if name.starts_with('#') {
+            return Visibility::Private;
+        }
+
+        match self.accessibility(node, source_code) {
+            Some("private") => Visibility::Private,
+            Some("protected") => Visibility::Protected,
+            _ => Visibility::Public,
+        }
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.walk_scoped(node, source_code, file_events, &mut Vec::new())
+    }
+}
+
+impl JavaScriptParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.record_snippets = enabled;
+        self
+    }
+
+    // `scope` holds the names of the enclosing functions, innermost last, so calls can name their caller.
+    fn walk_scoped(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let should_parse_children: bool = self.parse_node(node, source_code, file_events, scope)?;
+
+        if should_parse_children {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.walk_scoped(&child, source_code, file_events, scope)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<bool, Box<dyn std::error::Error>> {
+        match node.kind() {
+            "function_declaration" | "generator_function_declaration" | "method_definition" | "abstract_method_signature" => {
+                let Some(name) = node.child_by_field_name("name") else {
+                    return Ok(true);
+                };
+                let name = self.node_text(name, source_code).to_string();
+                self.parse_function(node, &name, node, source_code, file_events, scope)?;
+                Ok(false)
+            }
+            "variable_declarator" => {
+                let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) else {
+                    return Ok(true);
+                };
+
+                // `const f = () => ...` and `const f = function () {...}` are named by their binding.
+                if name.kind() == "identifier"
+                    && matches!(value.kind(), "arrow_function" | "function_expression" | "function" | "generator_function") {
+                    let name = self.node_text(name, source_code).to_string();
+                    self.parse_function(&value, &name, node, source_code, file_events, scope)?;
+                    return Ok(false);
+                }
+
+                Ok(true)
+            }
+            "class_declaration" | "abstract_class_declaration" => {
+                if let Some(class_event) = self.parse_class(node, source_code) {
+                    file_events.add_event(class_event);
+                }
+                Ok(true)
+            }
+            // TypeScript interfaces, type aliases and enums have no runtime class but are reported as one.
+            "interface_declaration" | "type_alias_declaration" | "enum_declaration" => {
+                if let Some(type_event) = self.parse_class(node, source_code) {
+                    file_events.add_event(type_event);
+                }
+                Ok(false)
+            }
+            "import_statement" => {
+                if let Some(import_event) = self.parse_import(node, source_code) {
+                    file_events.add_event(import_event);
+                }
+                Ok(false)
+            }
+            "call_expression" => {
+                if let Some(require_event) = self.parse_require(node, source_code) {
+                    file_events.add_event(require_event);
+                }
+                if let Some(call_event) = self.parse_call(node, source_code, scope) {
+                    file_events.add_event(call_event);
+                }
+                Ok(true)
+            }
+            "jsx_opening_element" | "jsx_self_closing_element" => {
+                if let Some(component_event) = jsx::component_reference(node, source_code, scope.last().cloned()) {
+                    file_events.add_event(component_event);
+                }
+                Ok(true)
+            }
+            "comment" => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    // `definition` is the node whose span and export status describe the function: the declarator for
+    // `const f = () => ...`, otherwise the function itself.
+    fn parse_function(&self, function: &Node, name: &str, definition: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let (parameters, parameters_detailed) = match function.child_by_field_name("parameters") {
+            Some(params_node) => self.extract_parameters(&params_node, source_code),
+            // A single bare arrow parameter (`x => x * 2`) has no parentheses.
+            None => match function.child_by_field_name("parameter") {
+                Some(parameter) => self.extract_parameters_from(&[parameter], source_code),
+                None => (Vec::new(), Vec::new()),
+            },
+        };
+
+        let return_type = function.child_by_field_name("return_type")
+            .map(|return_type| self.type_text(return_type, source_code));
+
+        let yields = function.kind().starts_with("generator_")
+            || self.has_anonymous_child(function, "*");
+        let is_async = self.has_anonymous_child(function, "async");
+
+        file_events.add_event(ParseEvent::FunctionDefinition {
+            name: name.to_string(),
+            start_line: definition.start_position().row + 1,
+            start_column: definition.start_position().column + 1,
+            end_line: definition.end_position().row + 1,
+            parameters,
+            parameters_detailed,
+            return_type,
+            visibility: self.symbol_visibility(definition, name, source_code),
+            is_generator: yields && !is_async,
+            is_async_generator: yields && is_async,
+            snippet: self.snippet(definition, source_code),
+        });
+
+        if let Some(body) = function.child_by_field_name("body") {
+            scope.push(name.to_string());
+            self.walk_scoped(&body, source_code, file_events, scope)?;
+            scope.pop();
+        }
+
+        Ok(())
+    }
+
+    fn parse_class(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor: TreeCursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                let field = match member.kind() {
+                    "field_definition" | "public_field_definition" | "property_signature" | "enum_assignment" =>
+                        member.child_by_field_name("property").or_else(|| member.child_by_field_name("name")),
+                    "property_identifier" => Some(member),
+                    _ => None,
+                };
+                if let Some(field) = field {
+                    fields.push(self.node_text(field, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::ClassDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            fields,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // `import a, { b as c } from 'm'` records the imported names (`a`, `b`), not local aliases; `* as ns` is a namespace, not a wildcard.
+    fn parse_import(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let module = self.string_value(&node.child_by_field_name("source")?, source_code);
+
+        let mut items: Vec<String> = Vec::new();
+        let mut cursor: TreeCursor = node.walk();
+        for clause in node.named_children(&mut cursor).filter(|child| child.kind() == "import_clause") {
+            let mut clause_cursor: TreeCursor = clause.walk();
+            for binding in clause.named_children(&mut clause_cursor) {
+                match binding.kind() {
+                    "identifier" => items.push(self.node_text(binding, source_code).to_string()),
+                    "named_imports" => {
+                        let mut named_cursor: TreeCursor = binding.walk();
+                        for specifier in binding.named_children(&mut named_cursor) {
+                            if let Some(imported) = specifier.child_by_field_name("name") {
+                                items.push(self.node_text(imported, source_code).to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Some(ParseEvent::ImportStatement {
+            module,
+            items,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            is_wildcard: false,
+        })
+    }
+
+    // CommonJS `const x = require('m')` / `const { a, b } = require('m')`; items come from the binding pattern.
+    fn parse_require(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let function = node.child_by_field_name("function")?;
+        if function.kind() != "identifier" || self.node_text(function, source_code) != "require" {
+            return None;
+        }
+
+        let module = node.child_by_field_name("arguments")?.named_child(0).filter(|argument| argument.kind() == "string")?;
+
+        let mut items: Vec<String> = Vec::new();
+        if let Some(binding) = node.parent()
+            .filter(|parent| parent.kind() == "variable_declarator")
+            .and_then(|declarator| declarator.child_by_field_name("name")) {
+            match binding.kind() {
+                "identifier" => items.push(self.node_text(binding, source_code).to_string()),
+                "object_pattern" => {
+                    let mut cursor: TreeCursor = binding.walk();
+                    for property in binding.named_children(&mut cursor) {
+                        let key = match property.kind() {
+                            "shorthand_property_identifier_pattern" => Some(property),
+                            "pair_pattern" => property.child_by_field_name("key"),
+                            _ => None,
+                        };
+                        if let Some(key) = key {
+                            items.push(self.node_text(key, source_code).to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(ParseEvent::ImportStatement {
+            module: self.string_value(&module, source_code),
+            items,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            is_wildcard: false,
+        })
+    }
+
+    fn parse_call(&self, node: &Node, source_code: &str, scope: &[String]) -> Option<ParseEvent> {
+        let callee = node.child_by_field_name("function")?;
+
+        let mut arguments: Vec<String> = Vec::new();
+        if let Some(args_node) = node.child_by_field_name("arguments") {
+            let mut cursor: TreeCursor = args_node.walk();
+            for argument in args_node.named_children(&mut cursor) {
+                if argument.kind() != "comment" {
+                    arguments.push(self.node_text(argument, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::FunctionCall {
+            caller_function: scope.last().cloned(),
+            callee: self.node_text(callee, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            arguments,
+        })
+    }
+
+    //Helper functions
+
+    fn extract_parameters(&self, params_node: &Node, source_code: &str) -> (Vec<String>, Vec<Parameter>) {
+        let mut cursor: TreeCursor = params_node.walk();
+        let children: Vec<Node> = params_node.named_children(&mut cursor).collect();
+        self.extract_parameters_from(&children, source_code)
+    }
+
+    fn extract_parameters_from(&self, children: &[Node], source_code: &str) -> (Vec<String>, Vec<Parameter>) {
+        let mut parameters: Vec<String> = Vec::new();
+        let mut parameters_detailed: Vec<Parameter> = Vec::new();
+
+        for child in children {
+            let (name, default_value, kind) = match child.kind() {
+                "comment" => continue,
+                "assignment_pattern" => (
+                    child.child_by_field_name("left").map(|left| self.node_text(left, source_code)),
+                    child.child_by_field_name("right").map(|right| self.node_text(right, source_code).to_string()),
+                    ParameterKind::Positional,
+                ),
+                "rest_pattern" => (
+                    child.named_child(0).map(|target| self.node_text(target, source_code)),
+                    None,
+                    ParameterKind::VarPositional,
+                ),
+                // TypeScript wraps each parameter with its annotation: `x?: number = 1`, `...rest: T[]`.
+                "required_parameter" | "optional_parameter" => {
+                    let pattern = child.child_by_field_name("pattern");
+                    let kind = match pattern {
+                        Some(pattern) if pattern.kind() == "rest_pattern" => ParameterKind::VarPositional,
+                        _ => ParameterKind::Positional,
+                    };
+                    (
+                        pattern.map(|pattern| match pattern.kind() {
+                            "rest_pattern" => pattern.named_child(0).unwrap_or(pattern),
+                            _ => pattern,
+                        }).map(|target| self.node_text(target, source_code)),
+                        child.child_by_field_name("value").map(|value| self.node_text(value, source_code).to_string()),
+                        kind,
+                    )
+                }
+                _ => (Some(self.node_text(*child, source_code)), None, ParameterKind::Positional),
+            };
+
+            parameters.push(self.node_text(*child, source_code).to_string());
+            if let Some(name) = name {
+                parameters_detailed.push(Parameter {
+                    name: name.to_string(),
+                    param_type: child.child_by_field_name("type")
+                        .map(|param_type| self.type_text(param_type, source_code)),
+                    default_value,
+                    kind,
+                });
+            }
+        }
+
+        (parameters, parameters_detailed)
+    }
+
+    fn is_exported(&self, node: &Node) -> bool {
+        let declaration = match node.parent() {
+            Some(parent) if matches!(parent.kind(), "lexical_declaration" | "variable_declaration") => parent,
+            _ => *node,
+        };
+        declaration.parent().is_some_and(|parent| parent.kind() == "export_statement")
+    }
+
+    // TypeScript's `public`, `private` or `protected` modifier, if any.
+    fn accessibility<'a>(&self, node: &Node, source_code: &'a str) -> Option<&'a str> {
+        let mut cursor: TreeCursor = node.walk();
+        node.children(&mut cursor)
+            .find(|child| child.kind() == "accessibility_modifier")
+            .map(|modifier| self.node_text(modifier, source_code))
+    }
+
+    fn has_anonymous_child(&self, node: &Node, kind: &str) -> bool {
+        let mut cursor: TreeCursor = node.walk();
+        node.children(&mut cursor).any(|child| !child.is_named() && child.kind() == kind)
+    }
+
+    fn string_value(&self, string: &Node, source_code: &str) -> String {
+        self.node_text(*string, source_code)
+            .trim_matches(|c| c == '"' || c == '\'' || c == '`')
+            .to_string()
+    }
+
+    // Type annotations include their leading `:`.
+    fn type_text(&self, annotation: Node, source_code: &str) -> String {
+        self.node_text(annotation, source_code).trim_start_matches(':').trim().to_string()
+    }
+
+    fn snippet(&self, node: &Node, source_code: &str) -> Option<String> {
+        self.record_snippets.then(|| self.node_text(*node, source_code).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(suffix: &str, source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        JavaScriptParser::new().parse_file(source, file.path()).unwrap()
+    }
+
+    #[test]
+    fn capitalized_jsx_element_is_a_component_reference() {
+        let file_events = parse(".jsx", "function App() {\n  return <div><MyButton prop={x} /></div>;\n}\n");
+
+        let calls: Vec<(Option<&str>, &str, &[String])> = file_events.function_calls()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionCall { caller_function, callee, arguments, .. } => Some((caller_function.as_deref(), callee.as_str(), arguments.as_slice())),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(calls, vec![(Some("App"), "MyButton", &["prop={x}".to_string()][..])]);
+    }
+
+    fn function_names(file_events: &FileEvents) -> Vec<&str> {
+        file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arrow_functions_and_function_expressions_are_named_by_their_binding() {
+        let file_events = parse(".js", "function declared(a) {}\nconst arrow = (a, b = 2) => a + b;\nconst single = x => x * 2;\nlet expression = function () {};\nconst value = 42;\n");
+
+        assert_eq!(function_names(&file_events), vec!["declared", "arrow", "single", "expression"]);
+        let arrow_parameters: Vec<(String, Option<String>)> = file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, .. } if name == "arrow" => {
+                    Some(parameters_detailed.iter().map(|parameter| (parameter.name.clone(), parameter.default_value.clone())).collect())
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(arrow_parameters, vec![("a".to_string(), None), ("b".to_string(), Some("2".to_string()))]);
+    }
+
+    #[test]
+    fn class_declaration_with_its_fields_and_methods() {
+        let file_events = parse(".js", "class Counter {\n  count = 0;\n  increment() {\n    this.count++;\n  }\n}\n");
+
+        let classes: Vec<(&str, Vec<&str>, usize, usize)> = file_events.classes()
+            .filter_map(|event| match event {
+                ParseEvent::ClassDefinition { name, fields, start_line, end_line, .. } => Some((name.as_str(), fields.iter().map(String::as_str).collect(), *start_line, *end_line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(classes, vec![("Counter", vec!["count"], 1, 6)]);
+        assert_eq!(function_names(&file_events), vec!["increment"]);
+    }
+
+    fn imports(file_events: &FileEvents) -> Vec<(&str, Vec<&str>)> {
+        file_events.imports()
+            .filter_map(|event| match event {
+                ParseEvent::ImportStatement { module, items, .. } => Some((module.as_str(), items.iter().map(String::as_str).collect())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn es_imports_record_the_imported_names() {
+        let file_events = parse(".mjs", "import React from 'react';\nimport { useState as useLocal, useEffect } from \"react\";\nimport * as path from 'path';\nimport './side-effect.css';\n");
+
+        assert_eq!(imports(&file_events), vec![
+            ("react", vec!["React"]),
+            ("react", vec!["useState", "useEffect"]),
+            ("path", vec![]),
+            ("./side-effect.css", vec![]),
+        ]);
+    }
+
+    #[test]
+    fn commonjs_require_is_an_import() {
+        let file_events = parse(".cjs", "const fs = require('fs');\nconst { join, resolve: resolvePath } = require('path');\nrequire('dotenv');\n");
+
+        assert_eq!(imports(&file_events), vec![
+            ("fs", vec!["fs"]),
+            ("path", vec!["join", "resolve"]),
+            ("dotenv", vec![]),
+        ]);
+    }
+
+    #[test]
+    fn only_exported_declarations_are_public() {
+        let file_events = parse(".js", "export function shown() {}\nfunction hidden() {}\nexport const arrow = () => {};\nexport class Widget {\n  render() {}\n  #secret() {}\n}\nclass Local {}\n");
+
+        let visibilities: Vec<(&str, Visibility)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, visibility, .. } | ParseEvent::ClassDefinition { name, visibility, .. } => Some((name.as_str(), *visibility)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(visibilities, vec![
+            ("shown", Visibility::Public),
+            ("hidden", Visibility::Module),
+            ("arrow", Visibility::Public),
+            ("Widget", Visibility::Public),
+            ("render", Visibility::Public),
+            ("#secret", Visibility::Private),
+            ("Local", Visibility::Module),
+        ]);
+    }
+}
@@ -0,0 +1,67 @@
+use tree_sitter::{Node, TreeCursor};
+
+use crate::parser::event::ParseEvent;
+
+// Shared by the JSX-capable parsers. JSX treats lowercase names as host elements (`<div>`); capitalized
+// or dotted names refer to components, so each use is reported as a call to the component.
+pub fn component_reference(node: &Node, source_code: &str, caller_function: Option<String>) -> Option<ParseEvent> {
+    if !matches!(node.kind(), "jsx_opening_element" | "jsx_self_closing_element") {
+        return None;
+    }
+
+    let name = &source_code[node.child_by_field_name("name")?.byte_range()];
+    if !(name.starts_with(|c: char| c.is_uppercase()) || name.contains('.')) {
+        return None;
+    }
+
+    let mut cursor: TreeCursor = node.walk();
+    let arguments: Vec<String> = node.children_by_field_name("attribute", &mut cursor)
+        .map(|attribute| source_code[attribute.byte_range()].to_string())
+        .collect();
+
+    Some(ParseEvent::FunctionCall {
+        caller_function,
+        callee: name.to_string(),
+        line: node.start_position().row + 1,
+        column: node.start_position().column + 1,
+        arguments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tree_sitter::Parser;
+
+    fn component_references(source: &str) -> Vec<(String, usize, Vec<String>)> {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_javascript::language()).unwrap();
+        let tree = parser.parse(source, None).unwrap();
+
+        let mut references = Vec::new();
+        let mut pending = vec![tree.root_node()];
+        while let Some(node) = pending.pop() {
+            if let Some(ParseEvent::FunctionCall { callee, line, arguments, .. }) = component_reference(&node, source, None) {
+                references.push((callee, line, arguments));
+            }
+            let mut cursor = node.walk();
+            pending.extend(node.children(&mut cursor));
+        }
+        references.sort();
+        references
+    }
+
+    #[test]
+    fn capitalized_jsx_element_is_a_component_reference() {
+        let references = component_references("const view = <div>\n  <MyButton prop={x} />\n  <span>hi</span>\n</div>;\n");
+
+        assert_eq!(references, vec![("MyButton".to_string(), 2, vec!["prop={x}".to_string()])]);
+    }
+
+    #[test]
+    fn dotted_jsx_element_is_a_component_reference() {
+        let references = component_references("const view = <Menu.Item>Open</Menu.Item>;\n");
+
+        assert_eq!(references, vec![("Menu.Item".to_string(), 1, Vec::new())]);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod r#trait; 
 pub mod registry;
 pub mod python;
+pub mod ipynb;
+pub mod jsx;
+pub mod rust;
+pub mod javascript;
+pub mod typescript;
+pub mod golang;
+pub mod java;
+pub mod query;
 pub mod event;
\ No newline at end of file
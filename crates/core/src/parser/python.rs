@@ -1,9 +1,18 @@
+use std::collections::HashSet;
 use tree_sitter::{Language, Node, TreeCursor};
 use tree_sitter_python::language as python_language;
 
-use crate::parser::{event::{FileEvents, ParseEvent}, r#trait::LanguageParser};
+use crate::parser::{event::{self, AccessType, CommentMarkers, CommentType, DocType, FileEvents, Parameter, ParameterKind, ParseEvent, Severity, Visibility}, r#trait::LanguageParser};
 
-pub struct PythonParser;
+#[derive(Default)]
+pub struct PythonParser {
+    check_missing_docstrings: bool,
+    comment_markers: CommentMarkers,
+    include_decorators_in_span: bool,
+    record_snippets: bool,
+    skipped_node_kinds: HashSet<String>,
+    skipped_names: HashSet<String>,
+}
 
 impl LanguageParser for PythonParser {
     fn language(&self) -> Language {
@@ -14,252 +23,941 @@ impl LanguageParser for PythonParser {
         "python"
     }
 
+    fn language_kind(&self) -> event::Language {
+        event::Language::Python
+    }
+
     fn file_extensions(&self) -> &[&'static str] {
          &["py", "pyw", "pyi"]
     }
 
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+    }
+
+    fn set_comment_marker(&mut self, marker: &str, comment_type: CommentType) {
+        self.comment_markers.set(marker, comment_type);
+    }
+
+    // Module-level events (docstring, main guard, top-level code) come from statements, never from definitions.
+    fn splice_kinds(&self) -> &[&'static str] {
+        &["function_definition", "class_definition", "decorated_definition", "comment"]
+    }
+
     fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
-        let should_parse_children: bool = self.parse_node(node, source_code, file_events)?; 
+        self.walk_scoped(node, source_code, file_events, &mut Vec::new())
+    }
+}
+
+impl PythonParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_missing_docstring_check(mut self, enabled: bool) -> Self {
+        self.check_missing_docstrings = enabled;
+        self
+    }
+
+    // Start decorated functions and classes at their first decorator, so folding covers the whole block.
+    pub fn with_decorators_in_span(mut self, enabled: bool) -> Self {
+        self.include_decorators_in_span = enabled;
+        self
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.record_snippets = enabled;
+        self
+    }
+
+    pub fn with_comment_marker(mut self, marker: &str, comment_type: CommentType) -> Self {
+        self.comment_markers.set(marker, comment_type);
+        self
+    }
+
+    pub fn with_skipped_node_kinds(mut self, node_kinds: Vec<&str>) -> Self {
+        self.skipped_node_kinds.extend(node_kinds.into_iter().map(|kind| kind.to_string()));
+        self
+    }
+
+    pub fn with_skipped_names(mut self, names: Vec<&str>) -> Self {
+        self.skipped_names.extend(names.into_iter().map(|name| name.to_string()));
+        self
+    }
+
+    fn is_skipped(&self, node: &Node, source_code: &str) -> bool {
+        if self.skipped_node_kinds.contains(node.kind()) {
+            return true;
+        }
+
+        !self.skipped_names.is_empty() && node.child_by_field_name("name")
+            .is_some_and(|name| self.skipped_names.contains(self.node_text(name, source_code)))
+    }
+
+    // `scope` holds the names of the enclosing functions, innermost last, so calls can name their caller.
+    fn walk_scoped(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_skipped(node, source_code) {
+            return Ok(());
+        }
+
+        let should_parse_children: bool = self.parse_node(node, source_code, file_events, scope)?; 
 
         if should_parse_children {
             let mut cursor = node.walk(); 
             for child in node.children(&mut cursor) {
-                self.walk_tree(&child, source_code, file_events)?; 
+                self.walk_scoped(&child, source_code, file_events, scope)?; 
             }
         }
 
         Ok(())
     }
-}
 
-impl PythonParser {
-    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<bool, Box<dyn std::error::Error>> {
+    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<bool, Box<dyn std::error::Error>> {
         match node.kind() {
+            "module" => {
+                self.parse_module(node, source_code, file_events)?;
+                Ok(true)
+            }
             "function_definition" => {
                 if let Some(function_event) = self.parse_function(node, source_code)? {
+                    if self.check_missing_docstrings {
+                        self.check_docstring(node, &function_event, file_events);
+                    }
+                    let async_event = self.parse_async(node, &function_event);
                     file_events.add_event(function_event);
+                    if let Some(async_event) = async_event {
+                        file_events.add_event(async_event);
+                    }
                 }
 
                 if let Some(body) = node.child_by_field_name("body") {
-                    self.walk_tree(&body, source_code, file_events)?;
-                }
+                    if let Some(doc_event) = self.parse_docstring(node, &body, source_code, DocType::Function) {
+                        file_events.add_event(doc_event);
+                    }
 
-                Ok(false)
-            }
-            /* 
-            "class_definition" => {
-                if let Some(class_event) = self.parse_class(node, source_code)? {
-                    file_events.add_event(class_event);
+                    let function_name = node.child_by_field_name("name").map(|name| self.node_text(name, source_code).to_string());
+                    if let Some(name) = &function_name {
+                        scope.push(name.clone());
+                    }
+                    self.walk_scoped(&body, source_code, file_events, scope)?;
+                    if function_name.is_some() {
+                        scope.pop();
+                    }
                 }
+
                 Ok(false)
             }
-            
-            "assignment" => {
-                if let Some(variable_event) = self.parse_variable(node, source_code)? {
-                    file_events.add_event(variable_event);
+            "call" => {
+                if let Some(call_event) = self.parse_call(node, source_code, scope) {
+                    file_events.add_event(call_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "import_statement" | "import_from_statement" => {
-                if let Some(import_event) = self.parse_import(node, source_code)? {
-                    file_events.add_event(import_event);
+            "list_comprehension" | "dictionary_comprehension" | "set_comprehension" => {
+                for comprehension_event in self.parse_comprehension(node, source_code) {
+                    file_events.add_event(comprehension_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "if_statement" => {
-                if let Some(conditional_block_event) = self.parse_if_statement(node, source_code)?{
-                    file_events.add_event(conditional_block_event);
+            "with_item" => {
+                if let Some(context_manager_event) = self.parse_with_item(node, source_code) {
+                    file_events.add_event(context_manager_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "match_statement" => {
-                if let Some(conditional_block_event) = self.parse_match_statement(node, source_code)?{
-                    file_events.add_event(conditional_block_event);
-                }
+            "comment" => {
+                let content = self.node_text(*node, source_code).trim_start_matches('#').trim().to_string();
+                file_events.add_event(ParseEvent::Comment {
+                    comment_type: self.comment_markers.classify(&content, CommentType::Line),
+                    content,
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                });
                 Ok(false)
             }
-            "try_statement" => {
-                if let Some(conditional_block_event) = self.parse_try_statement(node, source_code)?{
-                    file_events.add_event(conditional_block_event);
-                }
-                Ok(false)
+            "raise_statement" => {
+                file_events.add_event(self.parse_raise(node, source_code));
+                Ok(true)
             }
-            "while_statement" => {
-                if let Some(control_flow_event) = self.parse_while_statement(node, source_code)?{
-                    file_events.add_event(control_flow_event);
+            "delete_statement" => {
+                for delete_event in self.parse_delete(node, source_code, scope) {
+                    file_events.add_event(delete_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "for_statement" => {
-                if let Some(control_flow_event) = self.parse_for_statement(node, source_code)?{
-                    file_events.add_event(control_flow_event);
+            "class_definition" => {
+                if let Some(class_event) = self.parse_class(node, source_code)? {
+                    file_events.add_event(class_event);
                 }
-                Ok(false)
-            }
-            "parameter" => {
-                if let Some(parameter_event) = self.parse_parameter(node, source_code)?{
-                    file_events.add_event(parameter_event);
+                if let Some(inheritance_event) = self.parse_inheritance(node, source_code) {
+                    file_events.add_event(inheritance_event);
                 }
-                Ok(false)
-            }
-            "decorator" => {
-                if let Some(decorator_event) = self.parse_decorator(node, source_code)?{
-                    file_events.add_event(decorator_event);
+                for attribute_event in self.parse_class_attributes(node, source_code) {
+                    file_events.add_event(attribute_event);
                 }
-                Ok(false)
-            }
-            "block" => {
-                if let Some(block_event) = self.parse_block(node, source_code)?{
-                    file_events.add_event(block_event);
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    if let Some(doc_event) = self.parse_docstring(node, &body, source_code, DocType::Class) {
+                        file_events.add_event(doc_event);
+                    }
+                    self.walk_scoped(&body, source_code, file_events, scope)?;
                 }
+
                 Ok(false)
             }
-            "dotted_name" => {
-                if let Some(dotted_name_event) = self.parse_dotted_name(node, source_code)?{
-                    file_events.add_event(dotted_name_event);
+            "import_statement" | "import_from_statement" | "future_import_statement" => {
+                for import_event in self.parse_import(node, source_code)? {
+                    if let ParseEvent::ImportStatement { module, line, is_wildcard: true, .. } = &import_event {
+                        let message = format!("Wildcard import from `{}` hides which names are used", module);
+                        file_events.add_diagnostic(Severity::Warning, message, *line);
+                    }
+                    file_events.add_event(import_event);
                 }
                 Ok(false)
             }
-            "expression_statement" => {
-                if let Some(event) = self.parse_expression_statement(node, source_code)?{
-                    file_events.add_event(event);
+            "decorator" => {
+                if let Some(decorator_event) = self.parse_decorator(node, source_code)? {
+                    file_events.add_event(decorator_event);
                 }
                 Ok(false)
             }
-            "identifier" => {
-                if let Some(event) = self.parse_identifier(node, source_code)?{
-                    file_events.add_event(event);
+            "while_statement" => {
+                if let Some(control_flow_event) = self.parse_while_statement(node, source_code)? {
+                    file_events.add_event(control_flow_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "argument_list" => {
-                if let Some(event) = self.parse_argument_list(node, source_code)?{
-                    file_events.add_event(event);
+            "for_statement" => {
+                if let Some(control_flow_event) = self.parse_for_statement(node, source_code)? {
+                    file_events.add_event(control_flow_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "list" => {
-                if let Some(event) = self.parse_list(node, source_code)?{
-                    file_events.add_event(event);
+            "if_statement" => {
+                for conditional_block_event in self.parse_if_statement(node, source_code)? {
+                    file_events.add_event(conditional_block_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "tuple" => {
-                if let Some(event) = self.parse_tuple(node, source_code)?{
-                    file_events.add_event(event);
+            "try_statement" => {
+                if let Some(try_block_event) = self.parse_try_statement(node, source_code)? {
+                    file_events.add_event(try_block_event);
                 }
-                Ok(false)
+                Ok(true)
             }
-            "return_type" => {
-                if let Some(event) = self.parse_return_type(node, source_code)?{
-                    file_events.add_event(event);
+            "assignment" => {
+                if scope.is_empty() {
+                    for variable_event in self.parse_variable(node, source_code)? {
+                        file_events.add_event(variable_event);
+                    }
                 }
-                Ok(false)
+                Ok(true)
             }
-            */
             _ => {Ok(true)}
         }
     }
     fn parse_function(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        let name: String = node.child_by_field_name("name").map(|n: Node<'_>| self.node_text(n.clone(), source_code).to_string()).unwrap();
-        let parameters: Vec<String> = if let Some(params_node) = node.child_by_field_name("parameters") {
-            self.extract_parameters(&params_node, source_code)?
+        // Error recovery can produce a function node without a name; there is nothing to report for it.
+        let Some(name) = node.child_by_field_name("name").map(|n: Node<'_>| self.node_text(n, source_code).to_string()) else {
+            return Ok(None);
+        };
+        let (parameters, parameters_detailed) = if let Some(params_node) = node.child_by_field_name("parameters") {
+            (self.extract_parameters(&params_node, source_code)?, self.extract_parameters_detailed(&params_node, source_code))
         } else {
-            Vec::new()
+            (Vec::new(), Vec::new())
         };
 
-        let return_type: Option<String> = node.child_by_field_name("return_type").map(|n: Node<'_>| self.node_text(n.clone(), source_code).to_string());
+        let return_type: Option<String> = node.child_by_field_name("return_type").map(|n: Node<'_>| self.node_text(n, source_code).to_string());
 
-        let start_line: usize = node.start_position().row + 1;
+        let (start_line, start_column): (usize, usize) = self.definition_start(node);
         let end_line: usize = node.end_position().row + 1;
 
-        let is_public: bool = !name.starts_with('_');
+        let visibility: Visibility = self.symbol_visibility(node, &name, source_code);
+
+        let yields: bool = node.child_by_field_name("body")
+            .map(|body| self.contains_yield(&body))
+            .unwrap_or(false);
+        let is_async: bool = node.child(0).is_some_and(|first| first.kind() == "async");
 
         Ok(Some(ParseEvent::FunctionDefinition {
             name,
             start_line,
+            start_column,
             end_line,
             parameters,
+            parameters_detailed,
             return_type,
-            is_public,
+            visibility,
+            is_generator: yields && !is_async,
+            is_async_generator: yields && is_async,
+            snippet: self.snippet(node, source_code),
         }))
     }
 
-
     fn parse_class(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return Ok(None);
+        };
+        let name: String = self.node_text(name_node, source_code).to_string();
+
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor: TreeCursor = body.walk();
+            for statement in body.named_children(&mut cursor) {
+                if statement.kind() != "expression_statement" {
+                    continue;
+                }
+                if let Some(assignment) = statement.named_child(0).filter(|n| n.kind() == "assignment") {
+                    fields.extend(self.assignment_targets(&assignment, source_code).into_iter().map(|(name, _)| name));
+                }
+            }
+        }
+
+        let (start_line, start_column): (usize, usize) = self.definition_start(node);
+        let end_line: usize = node.end_position().row + 1;
+
+        let visibility: Visibility = self.symbol_visibility(node, &name, source_code);
+
+        Ok(Some(ParseEvent::ClassDefinition {
+            name,
+            start_line,
+            start_column,
+            end_line,
+            fields,
+            visibility,
+            snippet: self.snippet(node, source_code),
+        }))
+    }
+
+    // Only module- and class-level assignments define variables; the caller skips anything inside a
+    // function body, where assignments are locals. Conditionals and loops don't open a scope in Python.
+    fn parse_variable(&self, node: &Node, source_code: &str) -> Result<Vec<ParseEvent>, Box<dyn std::error::Error>> {
+        // The inner links of `a = b = 0` were already covered when the outermost assignment was parsed.
+        if node.parent().is_some_and(|parent| parent.kind() == "assignment") {
+            return Ok(Vec::new());
+        }
+
+        let var_type = node.child_by_field_name("type")
+            .map(|annotation| self.node_text(annotation, source_code).to_string());
+        let line = node.start_position().row + 1;
+        let column = node.start_position().column + 1;
+        let snippet = self.snippet(node, source_code);
+
+        // Each target of `a = b = 0` or `x, y = f()` is its own variable; a starred `*rest` always collects a list.
+        let variables = self.assignment_targets(node, source_code).into_iter()
+            .map(|(name, starred)| ParseEvent::VariableDefinition {
+                visibility: self.symbol_visibility(node, &name, source_code),
+                is_constant: name.chars().any(char::is_alphabetic) && !name.chars().any(char::is_lowercase),
+                name,
+                var_type: if starred { Some("list".to_string()) } else { var_type.clone() },
+                line,
+                column,
+                snippet: snippet.clone(),
+            })
+            .collect();
+
+        Ok(variables)
+    }
+
+    // Class attributes are assigned in the class body; instance attributes are assigned through the
+    // receiver (`self.x = ...`) anywhere in `__init__`.
+    fn parse_class_attributes(&self, node: &Node, source_code: &str) -> Vec<ParseEvent> {
+        let (Some(name), Some(body)) = (node.child_by_field_name("name"), node.child_by_field_name("body")) else {
+            return Vec::new();
+        };
+        let class_name = self.node_text(name, source_code);
+
+        let mut attributes: Vec<ParseEvent> = Vec::new();
+        let mut cursor: TreeCursor = body.walk();
+        for statement in body.named_children(&mut cursor) {
+            let definition = match statement.kind() {
+                "decorated_definition" => statement.child_by_field_name("definition"),
+                _ => Some(statement),
+            };
+
+            match definition {
+                Some(assignment_statement) if assignment_statement.kind() == "expression_statement" => {
+                    if let Some(assignment) = assignment_statement.named_child(0).filter(|n| n.kind() == "assignment") {
+                        let line = assignment.start_position().row + 1;
+                        let column = assignment.start_position().column + 1;
+                        attributes.extend(self.assignment_targets(&assignment, source_code).into_iter()
+                            .map(|(attribute, _)| ParseEvent::PythonClassAttribute {
+                                class_name: class_name.to_string(),
+                                attribute,
+                                is_instance: false,
+                                line,
+                                column,
+                            }));
+                    }
+                }
+                Some(function) if function.kind() == "function_definition"
+                    && function.child_by_field_name("name").is_some_and(|n| self.node_text(n, source_code) == "__init__") => {
+                    let receiver = function.child_by_field_name("parameters")
+                        .and_then(|params| params.named_child(0))
+                        .filter(|param| param.kind() == "identifier")
+                        .map(|param| self.node_text(param, source_code))
+                        .unwrap_or("self");
+                    if let Some(init_body) = function.child_by_field_name("body") {
+                        self.collect_instance_attributes(&init_body, class_name, receiver, source_code, &mut attributes);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        attributes
+    }
+
+    fn collect_instance_attributes(&self, node: &Node, class_name: &str, receiver: &str, source_code: &str, attributes: &mut Vec<ParseEvent>) {
+        let mut cursor: TreeCursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                // Nested functions and classes have their own receiver.
+                "function_definition" | "lambda" | "class_definition" => {}
+                "assignment" | "augmented_assignment" => {
+                    if let Some(left) = child.child_by_field_name("left") {
+                        self.collect_receiver_targets(&left, class_name, receiver, source_code, attributes);
+                    }
+                    if let Some(right) = child.child_by_field_name("right") {
+                        self.collect_instance_attributes(&right, class_name, receiver, source_code, attributes);
+                    }
+                }
+                _ => self.collect_instance_attributes(&child, class_name, receiver, source_code, attributes),
+            }
+        }
     }
 
-    fn parse_variable(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn collect_receiver_targets(&self, target: &Node, class_name: &str, receiver: &str, source_code: &str, attributes: &mut Vec<ParseEvent>) {
+        match target.kind() {
+            "attribute" => {
+                let is_receiver = target.child_by_field_name("object")
+                    .is_some_and(|object| object.kind() == "identifier" && self.node_text(object, source_code) == receiver);
+                if let (true, Some(attribute)) = (is_receiver, target.child_by_field_name("attribute")) {
+                    let attribute = self.node_text(attribute, source_code).to_string();
+                    let already_seen = attributes.iter().any(|event| matches!(event,
+                        ParseEvent::PythonClassAttribute { attribute: seen, is_instance: true, .. } if *seen == attribute));
+                    if !already_seen {
+                        attributes.push(ParseEvent::PythonClassAttribute {
+                            class_name: class_name.to_string(),
+                            attribute,
+                            is_instance: true,
+                            line: target.start_position().row + 1,
+                            column: target.start_position().column + 1,
+                        });
+                    }
+                }
+            }
+            "pattern_list" | "tuple_pattern" | "list_pattern" | "list_splat_pattern" => {
+                let mut cursor: TreeCursor = target.walk();
+                for child in target.named_children(&mut cursor) {
+                    self.collect_receiver_targets(&child, class_name, receiver, source_code, attributes);
+                }
+            }
+            _ => {}
+        }
     }
 
-    fn parse_import(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // Keyword arguments (`metaclass=...`) and splats in the superclass list aren't parents.
+    fn parse_inheritance(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let child_class = node.child_by_field_name("name")?;
+        let superclasses = node.child_by_field_name("superclasses")?;
+
+        let mut cursor: TreeCursor = superclasses.walk();
+        let parent_classes: Vec<String> = superclasses.named_children(&mut cursor)
+            .filter(|base| !matches!(base.kind(), "keyword_argument" | "list_splat" | "dictionary_splat" | "comment"))
+            .map(|base| self.node_text(base, source_code).to_string())
+            .collect();
+
+        if parent_classes.is_empty() {
+            return None;
+        }
+
+        Some(ParseEvent::ClassInheritance {
+            child_class: self.node_text(child_class, source_code).to_string(),
+            parent_classes,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        })
     }
 
-    fn parse_if_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_import(&self, node: &Node, source_code: &str) -> Result<Vec<ParseEvent>, Box<dyn std::error::Error>> {
+        let line: usize = node.start_position().row + 1;
+        let column: usize = node.start_position().column + 1;
+
+        // Aliases are dropped: `import numpy as np` records `numpy`, `from a import b as c` records `b`.
+        let mut names: Vec<String> = Vec::new();
+        let mut cursor: TreeCursor = node.walk();
+        for name_node in node.children_by_field_name("name", &mut cursor) {
+            let imported: Node = if name_node.kind() == "aliased_import" {
+                name_node.child_by_field_name("name").unwrap_or(name_node)
+            } else {
+                name_node
+            };
+            names.push(self.node_text(imported, source_code).to_string());
+        }
+
+        if node.kind() == "import_statement" {
+            // `import os, sys` imports two unrelated modules, so each gets its own event.
+            return Ok(names.into_iter()
+                .map(|module| ParseEvent::ImportStatement {
+                    module,
+                    items: Vec::new(),
+                    line,
+                    column,
+                    is_wildcard: false,
+                })
+                .collect());
+        }
+
+        let module: String = match node.kind() {
+            "future_import_statement" => "__future__".to_string(),
+            _ => node.child_by_field_name("module_name")
+                .map(|n: Node<'_>| self.node_text(n, source_code).to_string())
+                .unwrap_or_default(),
+        };
+
+        let mut cursor: TreeCursor = node.walk();
+        let is_wildcard: bool = node.named_children(&mut cursor).any(|child| child.kind() == "wildcard_import");
+
+        Ok(vec![ParseEvent::ImportStatement {
+            module,
+            items: names,
+            line,
+            column,
+            is_wildcard,
+        }])
     }
 
-    fn parse_match_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // One event per branch; the `if` branch ends with its consequence so it doesn't span the elif/else clauses.
+    fn parse_if_statement(&self, node: &Node, source_code: &str) -> Result<Vec<ParseEvent>, Box<dyn std::error::Error>> {
+        let mut branches: Vec<ParseEvent> = Vec::new();
+
+        let if_end = node.child_by_field_name("consequence").unwrap_or(*node);
+        branches.push(ParseEvent::ConditionalBlock {
+            condition_type: "if".to_string(),
+            condition_summary: node.child_by_field_name("condition")
+                .map(|condition| self.node_text(condition, source_code).to_string()),
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: if_end.end_position().row + 1,
+        });
+
+        let mut cursor: TreeCursor = node.walk();
+        for clause in node.children_by_field_name("alternative", &mut cursor) {
+            let (condition_type, condition_summary) = match clause.kind() {
+                "elif_clause" => ("elif", clause.child_by_field_name("condition")
+                    .map(|condition| self.node_text(condition, source_code).to_string())),
+                "else_clause" => ("else", None),
+                _ => continue,
+            };
+
+            branches.push(ParseEvent::ConditionalBlock {
+                condition_type: condition_type.to_string(),
+                condition_summary,
+                start_line: clause.start_position().row + 1,
+                start_column: clause.start_position().column + 1,
+                end_line: clause.end_position().row + 1,
+            });
+        }
+
+        Ok(branches)
     }
 
     fn parse_try_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+        let mut exception_types: Vec<String> = Vec::new();
+        let mut has_finally = false;
+        let mut cursor: TreeCursor = node.walk();
+
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "except_clause" | "except_group_clause" => {
+                    if let Some(caught) = child.named_child(0).filter(|caught| caught.kind() != "block") {
+                        self.collect_exception_types(&caught, source_code, &mut exception_types);
+                    }
+                }
+                "finally_clause" => has_finally = true,
+                _ => {}
+            }
+        }
+
+        Ok(Some(ParseEvent::TryBlock {
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            exception_types,
+            has_finally,
+        }))
     }
 
-    fn parse_while_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_while_statement(&self, node: &Node, _source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
+        Ok(Some(ParseEvent::LoopBlock {
+            loop_type: "while".to_string(),
+            iterator_variable: None,
+            iterable: None,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+        }))
     }
 
     fn parse_for_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+        let iterator_variable = node.child_by_field_name("left")
+            .map(|left| self.node_text(left, source_code).to_string());
+        let iterable = node.child_by_field_name("right")
+            .map(|right| self.node_text(right, source_code).to_string());
+
+        Ok(Some(ParseEvent::LoopBlock {
+            loop_type: "for".to_string(),
+            iterator_variable,
+            iterable,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+        }))
     }
 
-    fn parse_block(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // Decorators sit beside the definition under a `decorated_definition`, so the target comes from the parent.
+    fn parse_decorator(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
+        let Some(expression) = node.named_child(0) else {
+            return Ok(None);
+        };
+
+        let Some(target) = node.parent()
+            .filter(|parent| parent.kind() == "decorated_definition")
+            .and_then(|parent| parent.child_by_field_name("definition"))
+            .and_then(|definition| definition.child_by_field_name("name"))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(ParseEvent::PythonDecorator {
+            target: self.node_text(target, source_code).to_string(),
+            decorator: self.node_text(expression, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        }))
     }
 
-    fn parse_parameter(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_call(&self, node: &Node, source_code: &str, scope: &[String]) -> Option<ParseEvent> {
+        let callee = node.child_by_field_name("function")?;
+        let arguments = node.child_by_field_name("arguments")
+            .map(|arguments| self.extract_arguments(&arguments, source_code))
+            .unwrap_or_default();
+
+        Some(ParseEvent::FunctionCall {
+            caller_function: scope.last().cloned(),
+            callee: self.node_text(callee, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            arguments,
+        })
     }
 
-    fn parse_decorator(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // One event per `for` clause, in source order, each sharing the comprehension's result expression,
+    // so `[y for x in xs for y in x]` yields (x, xs) then (y, x).
+    fn parse_comprehension(&self, node: &Node, source_code: &str) -> Vec<ParseEvent> {
+        let Some(body) = node.child_by_field_name("body") else {
+            return Vec::new();
+        };
+        let result_expression = self.node_text(body, source_code).to_string();
+
+        let mut cursor: TreeCursor = node.walk();
+        node.named_children(&mut cursor)
+            .filter(|clause| clause.kind() == "for_in_clause")
+            .filter_map(|clause| {
+                let left = clause.child_by_field_name("left")?;
+                let right = clause.child_by_field_name("right")?;
+                Some(ParseEvent::PythonListComprehension {
+                    result_expression: result_expression.clone(),
+                    iterator_variable: self.node_text(left, source_code).to_string(),
+                    iterable: self.node_text(right, source_code).to_string(),
+                    line: clause.start_position().row + 1,
+                    column: clause.start_position().column + 1,
+                })
+            })
+            .collect()
     }
 
-    fn parse_dotted_name(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // `with a() as x, b():` has one with_item per manager; the `as` binding wraps the value in an as_pattern.
+    fn parse_with_item(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let value = node.child_by_field_name("value")?;
+
+        let (context_expression, variable) = if value.kind() == "as_pattern" {
+            let alias = value.child_by_field_name("alias")
+                .map(|alias| self.node_text(alias, source_code).to_string());
+            (value.named_child(0)?, alias)
+        } else {
+            (value, None)
+        };
+
+        Some(ParseEvent::PythonContextManager {
+            variable,
+            context_expression: self.node_text(context_expression, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        })
     }
 
-    fn parse_expression_statement(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_raise(&self, node: &Node, source_code: &str) -> ParseEvent {
+        // `raise ValueError("bad")` names the exception through the call; a bare `raise` re-raises and has no type.
+        let exception_type: Option<String> = node.named_child(0)
+            .filter(|expr| expr.kind() != "comment")
+            .map(|expr| match expr.kind() {
+                "call" => expr.child_by_field_name("function").unwrap_or(expr),
+                _ => expr,
+            })
+            .map(|expr| self.node_text(expr, source_code).to_string());
+
+        ParseEvent::RaiseStatement {
+            exception_type,
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+        }
     }
 
-    fn parse_identifier(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // `del a, b[0]` unbinds each target separately; subscripts and attributes are reported as written.
+    fn parse_delete(&self, node: &Node, source_code: &str, scope: &[String]) -> Vec<ParseEvent> {
+        let Some(target) = node.named_child(0) else {
+            return Vec::new();
+        };
+
+        let targets: Vec<Node> = match target.kind() {
+            "expression_list" => {
+                let mut cursor: TreeCursor = target.walk();
+                target.named_children(&mut cursor).collect()
+            }
+            _ => vec![target],
+        };
+
+        targets.into_iter()
+            .filter(|target| target.kind() != "comment")
+            .map(|target| ParseEvent::VariableAccess {
+                variable: self.node_text(target, source_code).to_string(),
+                access_type: AccessType::Delete,
+                line: target.start_position().row + 1,
+                column: target.start_position().column + 1,
+                context: scope.last().cloned(),
+            })
+            .collect()
     }
 
-    fn parse_argument_list(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_module(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(docstring) = self.docstring_node(node) {
+            let module_name = file_events.file_path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            file_events.add_event(ParseEvent::DocComment {
+                target: module_name,
+                content: self.string_content(&docstring, source_code),
+                line: docstring.start_position().row + 1,
+                column: docstring.start_position().column + 1,
+                doc_type: DocType::Module,
+            });
+        }
+
+        let mut has_top_level_code = false;
+        let mut cursor: TreeCursor = node.walk();
+
+        for child in node.children(&mut cursor) {
+            if child.kind() == "if_statement" && self.is_main_guard(&child, source_code) {
+                file_events.add_event(ParseEvent::PythonMainGuard {
+                    start_line: child.start_position().row + 1,
+                    start_column: child.start_position().column + 1,
+                    end_line: child.end_position().row + 1,
+                });
+            } else if !has_top_level_code && self.is_executable_statement(&child) {
+                has_top_level_code = true;
+                file_events.add_event(ParseEvent::PythonTopLevelCode {
+                    line: child.start_position().row + 1,
+                    column: child.start_position().column + 1,
+                });
+            }
+        }
+
+        Ok(())
     }
 
-    fn parse_list(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    // `async def` keeps `async` as an anonymous leading token of the function_definition.
+    fn parse_async(&self, node: &Node, function_event: &ParseEvent) -> Option<ParseEvent> {
+        let ParseEvent::FunctionDefinition { name, start_line, start_column, .. } = function_event else {
+            return None;
+        };
+
+        node.child(0)
+            .filter(|first| first.kind() == "async")
+            .map(|_| ParseEvent::PythonAsyncFunction {
+                function_name: name.clone(),
+                line: *start_line,
+                column: *start_column,
+            })
     }
 
-    fn parse_tuple(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn check_docstring(&self, node: &Node, function_event: &ParseEvent, file_events: &mut FileEvents) {
+        let ParseEvent::FunctionDefinition { name, start_line, .. } = function_event else {
+            return;
+        };
+
+        let has_docstring = node.child_by_field_name("body")
+            .and_then(|body| self.docstring_node(&body))
+            .is_some();
+
+        if !has_docstring {
+            file_events.add_diagnostic(Severity::Warning, format!("Function `{}` has no docstring", name), *start_line);
+        }
     }
 
-    fn parse_return_type(&self, node: &Node, source_code: &str) -> Result<Option<ParseEvent>, Box<dyn std::error::Error>> {
-        todo!()
+    fn parse_docstring(&self, definition: &Node, body: &Node, source_code: &str, doc_type: DocType) -> Option<ParseEvent> {
+        let docstring = self.docstring_node(body)?;
+        let target = self.node_text(definition.child_by_field_name("name")?, source_code);
+
+        Some(ParseEvent::DocComment {
+            target: target.to_string(),
+            content: self.string_content(&docstring, source_code),
+            line: docstring.start_position().row + 1,
+            column: docstring.start_position().column + 1,
+            doc_type,
+        })
     }
 
     //Helper functions
 
+    // `a = b = 0` nests the second assignment on the right-hand side, so follow the chain.
+    // Target names paired with whether they were starred (`first, *rest = items`).
+    fn assignment_targets(&self, assignment: &Node, source_code: &str) -> Vec<(String, bool)> {
+        let mut targets: Vec<(String, bool)> = Vec::new();
+        let mut current: Option<Node> = Some(*assignment);
+
+        while let Some(node) = current.filter(|n| n.kind() == "assignment") {
+            if let Some(left) = node.child_by_field_name("left") {
+                self.collect_target_names(&left, false, source_code, &mut targets);
+            }
+            current = node.child_by_field_name("right");
+        }
+
+        targets
+    }
+
+    fn collect_target_names(&self, target: &Node, starred: bool, source_code: &str, names: &mut Vec<(String, bool)>) {
+        match target.kind() {
+            "identifier" => names.push((self.node_text(*target, source_code).to_string(), starred)),
+            "pattern_list" | "tuple_pattern" | "list_pattern" | "list_splat_pattern" => {
+                let starred = target.kind() == "list_splat_pattern";
+                let mut cursor: TreeCursor = target.walk();
+                for child in target.named_children(&mut cursor) {
+                    self.collect_target_names(&child, starred, source_code, names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // `except (A, B) as e:` wraps the caught types in an as_pattern and a tuple.
+    fn collect_exception_types(&self, caught: &Node, source_code: &str, exception_types: &mut Vec<String>) {
+        match caught.kind() {
+            "as_pattern" => {
+                if let Some(value) = caught.named_child(0) {
+                    self.collect_exception_types(&value, source_code, exception_types);
+                }
+            }
+            "tuple" | "parenthesized_expression" => {
+                let mut cursor: TreeCursor = caught.walk();
+                for element in caught.named_children(&mut cursor) {
+                    self.collect_exception_types(&element, source_code, exception_types);
+                }
+            }
+            "comment" => {}
+            _ => exception_types.push(self.node_text(*caught, source_code).to_string()),
+        }
+    }
+
+    fn definition_start(&self, node: &Node) -> (usize, usize) {
+        let start = node.parent()
+            .filter(|parent| self.include_decorators_in_span && parent.kind() == "decorated_definition")
+            .unwrap_or(*node);
+        (start.start_position().row + 1, start.start_position().column + 1)
+    }
+
+    fn snippet(&self, node: &Node, source_code: &str) -> Option<String> {
+        self.record_snippets.then(|| self.node_text(*node, source_code).to_string())
+    }
+
+    // Comments are named children in the tree but don't displace a docstring.
+    fn docstring_node<'a>(&self, body: &Node<'a>) -> Option<Node<'a>> {
+        let mut cursor: TreeCursor = body.walk();
+        let first_statement = body.named_children(&mut cursor).find(|child| child.kind() != "comment")?;
+        if first_statement.kind() != "expression_statement" {
+            return None;
+        }
+
+        first_statement.named_child(0).filter(|expr| expr.kind() == "string")
+    }
+
+    // Text between the opening and closing quotes, so prefixes and triple quotes are dropped but inner newlines stay.
+    fn string_content(&self, string: &Node, source_code: &str) -> String {
+        let mut cursor: TreeCursor = string.walk();
+        let children: Vec<Node> = string.children(&mut cursor).collect();
+
+        match (children.first(), children.last()) {
+            (Some(start), Some(end)) if start.kind() == "string_start" && end.kind() == "string_end" => {
+                source_code[start.end_byte()..end.start_byte()].to_string()
+            }
+            _ => self.node_text(*string, source_code).to_string(),
+        }
+    }
+
+    // Yields inside nested functions, lambdas or classes belong to those scopes, not the enclosing function.
+    fn contains_yield(&self, node: &Node) -> bool {
+        let mut cursor: TreeCursor = node.walk();
+        
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "yield" => return true,
+                "function_definition" | "lambda" | "class_definition" => {}
+                _ => {
+                    if self.contains_yield(&child) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    fn is_main_guard(&self, node: &Node, source_code: &str) -> bool {
+        let Some(condition) = node.child_by_field_name("condition") else {
+            return false;
+        };
+
+        let normalized: String = self.node_text(condition, source_code)
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == '\'' { '"' } else { c })
+            .collect();
+
+        normalized == r#"__name__=="__main__""# || normalized == r#""__main__"==__name__"#
+    }
+
+    // Definitions, imports, docstrings and plain assignments only shape the module namespace;
+    // anything else runs as a side effect of importing the file.
+    fn is_executable_statement(&self, node: &Node) -> bool {
+        match node.kind() {
+            "function_definition" | "class_definition" | "decorated_definition"
+            | "import_statement" | "import_from_statement" | "future_import_statement"
+            | "comment" | "pass_statement" => false,
+            "expression_statement" => {
+                let mut cursor: TreeCursor = node.walk();
+                let is_declarative = node.named_children(&mut cursor)
+                    .all(|child| matches!(child.kind(), "assignment" | "string" | "concatenated_string"));
+                !is_declarative
+            }
+            _ => node.is_named(),
+        }
+    }
+
+    // Incomplete signatures can leave an annotation or default without its node; the parameter is
+    // still reported, just without the missing part.
     fn extract_parameters(&self, params_node: &Node, source_code: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let mut parameters: Vec<String> = Vec::new();
         let mut cursor: TreeCursor = params_node.walk();
@@ -272,22 +970,22 @@ impl PythonParser {
                 "typed_parameter" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let param_name: &str = self.node_text(name_node, source_code);
-                        let param_type: String = child.child_by_field_name("type").map(|n: Node<'_>| format!(": {}", self.node_text(n, source_code))).unwrap();
+                        let param_type: String = child.child_by_field_name("type").map(|n: Node<'_>| format!(": {}", self.node_text(n, source_code))).unwrap_or_default();
                         parameters.push(format!("{}{}", param_name, param_type));
                     }
                 }
                 "default_parameter" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let param_name: &str = self.node_text(name_node, source_code);
-                        let default_value: String = child.child_by_field_name("value").map(|v| format!(" = {}", self.node_text(v, source_code))).unwrap();
+                        let default_value: String = child.child_by_field_name("value").map(|v| format!(" = {}", self.node_text(v, source_code))).unwrap_or_default();
                         parameters.push(format!("{}{}", param_name, default_value));
                     }
                 }
                 "typed_default_parameter" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let param_name: &str = self.node_text(name_node, source_code);
-                        let param_type: String = child.child_by_field_name("type").map(|n: Node<'_>| format!(": {}", self.node_text(n, source_code))).unwrap();
-                        let default_value: String = child.child_by_field_name("value").map(|n: Node<'_>| format!(" = {}", self.node_text(n, source_code))).unwrap();
+                        let param_type: String = child.child_by_field_name("type").map(|n: Node<'_>| format!(": {}", self.node_text(n, source_code))).unwrap_or_default();
+                        let default_value: String = child.child_by_field_name("value").map(|n: Node<'_>| format!(" = {}", self.node_text(n, source_code))).unwrap_or_default();
                         parameters.push(format!("{}{}{}", param_name, param_type, default_value));
                     }
                 }
@@ -297,4 +995,630 @@ impl PythonParser {
         
         Ok(parameters)
     }
-}
\ No newline at end of file
+
+    fn extract_arguments(&self, args_node: &Node, source_code: &str) -> Vec<String> {
+        let mut arguments: Vec<String> = Vec::new();
+        let mut cursor: TreeCursor = args_node.walk();
+
+        for child in args_node.named_children(&mut cursor) {
+            match child.kind() {
+                "comment" => {}
+                "keyword_argument" => {
+                    let name: &str = child.child_by_field_name("name").map(|n| self.node_text(n, source_code)).unwrap_or_default();
+                    let value: &str = child.child_by_field_name("value").map(|v| self.node_text(v, source_code)).unwrap_or_default();
+                    arguments.push(format!("{}={}", name, value));
+                }
+                _ => {
+                    arguments.push(self.node_text(child, source_code).to_string());
+                }
+            }
+        }
+
+        arguments
+    }
+
+    fn extract_parameters_detailed(&self, params_node: &Node, source_code: &str) -> Vec<Parameter> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+        let mut keyword_only: bool = false;
+        let mut cursor: TreeCursor = params_node.walk();
+
+        for child in params_node.children(&mut cursor) {
+            match child.kind() {
+                "positional_separator" => {
+                    for parameter in parameters.iter_mut() {
+                        if parameter.kind == ParameterKind::Positional {
+                            parameter.kind = ParameterKind::PositionalOnly;
+                        }
+                    }
+                }
+                "keyword_separator" => {
+                    keyword_only = true;
+                }
+                "identifier" | "typed_parameter" | "default_parameter" | "typed_default_parameter"
+                | "list_splat_pattern" | "dictionary_splat_pattern" => {
+                    // `*args: int` has no name field; the splat pattern is its first named child instead.
+                    let target: Node = child.child_by_field_name("name")
+                        .or_else(|| if child.kind() == "typed_parameter" { child.named_child(0) } else { None })
+                        .unwrap_or(child);
+
+                    let kind: ParameterKind = match target.kind() {
+                        "list_splat_pattern" => ParameterKind::VarPositional,
+                        "dictionary_splat_pattern" => ParameterKind::VarKeyword,
+                        _ if keyword_only => ParameterKind::KeywordOnly,
+                        _ => ParameterKind::Positional,
+                    };
+
+                    if kind == ParameterKind::VarPositional {
+                        keyword_only = true;
+                    }
+
+                    let name: &str = self.node_text(target, source_code).trim_start_matches('*');
+                    parameters.push(Parameter {
+                        name: name.to_string(),
+                        param_type: child.child_by_field_name("type").map(|n: Node<'_>| self.node_text(n, source_code).to_string()),
+                        default_value: child.child_by_field_name("value").map(|n: Node<'_>| self.node_text(n, source_code).to_string()),
+                        kind,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        parameters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> FileEvents {
+        parse_with(PythonParser::new(), source)
+    }
+
+    fn parse_with(parser: PythonParser, source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(".py").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        parser.parse_file(source, file.path()).unwrap()
+    }
+
+    #[test]
+    fn main_guard_is_detected() {
+        let file_events = parse("def main():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n");
+
+        assert!(file_events.has_main_guard());
+    }
+
+    #[test]
+    fn library_module_has_no_main_guard() {
+        let file_events = parse("import os\n\ndef helper():\n    return os.getcwd()\n");
+
+        assert!(!file_events.has_main_guard());
+        assert!(!file_events.has_top_level_code());
+    }
+
+    fn generator_flags(file_events: &FileEvents, function: &str) -> (bool, bool) {
+        file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, is_generator, is_async_generator, .. } if name == function => {
+                    Some((*is_generator, *is_async_generator))
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn function_with_yield_is_a_generator() {
+        let file_events = parse("def f(xs):\n    for x in xs:\n        yield x\n\ndef g():\n    return 1\n");
+
+        assert_eq!(generator_flags(&file_events, "f"), (true, false));
+        assert_eq!(generator_flags(&file_events, "g"), (false, false));
+    }
+
+    #[test]
+    fn async_function_with_yield_is_an_async_generator() {
+        let file_events = parse("async def f(xs):\n    for x in xs:\n        yield x\n");
+
+        assert_eq!(generator_flags(&file_events, "f"), (false, true));
+    }
+
+    #[test]
+    fn yield_in_nested_function_does_not_make_outer_a_generator() {
+        let file_events = parse("def outer():\n    def inner():\n        yield 1\n    return inner\n");
+
+        assert_eq!(generator_flags(&file_events, "outer"), (false, false));
+        assert_eq!(generator_flags(&file_events, "inner"), (true, false));
+    }
+
+    #[test]
+    fn missing_docstring_is_reported_when_enabled() {
+        let source = "def documented():\n    \"\"\"Does things.\"\"\"\n    pass\n\ndef undocumented():\n    pass\n";
+
+        let checked = parse_with(PythonParser::new().with_missing_docstring_check(true), source);
+        let unchecked = parse(source);
+
+        assert_eq!(checked.diagnostics().len(), 1);
+        assert_eq!(checked.diagnostics()[0].severity, Severity::Warning);
+        assert_eq!(checked.diagnostics()[0].line, 5);
+        assert!(checked.diagnostics()[0].message.contains("`undocumented`"));
+        assert!(unchecked.diagnostics().is_empty());
+    }
+
+    fn parameter_kinds(file_events: &FileEvents, function: &str) -> Vec<(String, ParameterKind)> {
+        file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, .. } if name == function => {
+                    Some(parameters_detailed.iter().map(|parameter| (parameter.name.clone(), parameter.kind)).collect())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn positional_and_keyword_only_markers_classify_parameters() {
+        let file_events = parse("def f(a, /, b, *, c):\n    pass\n");
+
+        assert_eq!(parameter_kinds(&file_events, "f"), vec![
+            ("a".to_string(), ParameterKind::PositionalOnly),
+            ("b".to_string(), ParameterKind::Positional),
+            ("c".to_string(), ParameterKind::KeywordOnly),
+        ]);
+    }
+
+    #[test]
+    fn variadic_parameters_are_classified() {
+        let file_events = parse("def g(x, *args, y=1, **kwargs):\n    pass\n");
+
+        assert_eq!(parameter_kinds(&file_events, "g"), vec![
+            ("x".to_string(), ParameterKind::Positional),
+            ("args".to_string(), ParameterKind::VarPositional),
+            ("y".to_string(), ParameterKind::KeywordOnly),
+            ("kwargs".to_string(), ParameterKind::VarKeyword),
+        ]);
+    }
+
+    fn function_names(file_events: &FileEvents) -> Vec<&str> {
+        file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skipped_names_produce_no_events() {
+        let source = "class Point:\n    def __repr__(self):\n        return 'Point'\n\n    def norm(self):\n        return 0\n";
+
+        let file_events = parse_with(PythonParser::new().with_skipped_names(vec!["__repr__"]), source);
+
+        assert_eq!(function_names(&file_events), vec!["norm"]);
+        assert_eq!(function_names(&parse(source)), vec!["__repr__", "norm"]);
+    }
+
+    fn definition_name(event: &ParseEvent) -> &str {
+        match event {
+            ParseEvent::FunctionDefinition { name, .. }
+            | ParseEvent::ClassDefinition { name, .. }
+            | ParseEvent::VariableDefinition { name, .. } => name,
+            _ => "",
+        }
+    }
+
+    #[test]
+    fn public_api_excludes_underscore_names() {
+        let source = "VERSION = 1
+_cache = {}
+
+def load():
+    pass
+
+def _helper():
+    pass
+
+class Client:
+    pass
+
+class _Session:
+    pass
+";
+
+        let file_events = parse(source);
+        let names: Vec<&str> = file_events.public_api().into_iter().map(definition_name).collect();
+
+        assert_eq!(names, vec!["VERSION", "load", "Client"]);
+    }
+
+    #[test]
+    fn snippet_holds_the_definition_source() {
+        let function = "def greet(name):\n    return f\"hi {name}\"";
+        let source = format!("import os\n\n{}\n", function);
+
+        let file_events = parse_with(PythonParser::new().with_snippets(true), &source);
+        let snippet = file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { snippet, .. } => snippet.clone(),
+                _ => None,
+            });
+
+        assert_eq!(snippet.as_deref(), Some(function));
+        assert!(parse(&source).functions().all(|event| matches!(event, ParseEvent::FunctionDefinition { snippet: None, .. })));
+    }
+
+    #[test]
+    fn raise_captures_the_exception_type() {
+        let file_events = parse("def check(x):\n    if x < 0:\n        raise ValueError(\"bad\")\n");
+
+        let raises: Vec<(Option<&str>, usize)> = file_events.raises()
+            .filter_map(|event| match event {
+                ParseEvent::RaiseStatement { exception_type, line, .. } => Some((exception_type.as_deref(), *line)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(raises, vec![(Some("ValueError"), 3)]);
+    }
+
+    #[test]
+    fn keyword_arguments_keep_their_names() {
+        let file_events = parse("f(1, key=2)\n");
+
+        let arguments = file_events.function_calls()
+            .find_map(|event| match event {
+                ParseEvent::FunctionCall { callee, arguments, .. } if callee == "f" => Some(arguments.clone()),
+                _ => None,
+            });
+
+        assert_eq!(arguments, Some(vec!["1".to_string(), "key=2".to_string()]));
+    }
+
+    #[test]
+    fn parsed_file_reports_the_python_language() {
+        let file_events = parse("x = 1\n");
+
+        assert_eq!(file_events.language, event::Language::Python);
+        assert_eq!(file_events.language_name(), "python");
+    }
+
+    fn imports(file_events: &FileEvents) -> Vec<(String, Vec<String>, bool)> {
+        file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::ImportStatement { module, items, is_wildcard, .. } => Some((module.clone(), items.clone(), *is_wildcard)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plain_import_yields_one_event_per_module() {
+        assert_eq!(imports(&parse("import os, sys\n")), vec![
+            ("os".to_string(), vec![], false),
+            ("sys".to_string(), vec![], false),
+        ]);
+    }
+
+    #[test]
+    fn aliased_import_records_the_module() {
+        assert_eq!(imports(&parse("import numpy as np\n")), vec![("numpy".to_string(), vec![], false)]);
+    }
+
+    #[test]
+    fn from_import_collects_its_items() {
+        assert_eq!(imports(&parse("from collections import OrderedDict, defaultdict\n")), vec![
+            ("collections".to_string(), vec!["OrderedDict".to_string(), "defaultdict".to_string()], false),
+        ]);
+    }
+
+    #[test]
+    fn wildcard_import_has_no_items() {
+        assert_eq!(imports(&parse("from x import *\n")), vec![("x".to_string(), vec![], true)]);
+    }
+
+    #[test]
+    fn docstrings_are_doc_comments_for_their_definition() {
+        let file_events = parse("class Shape:\n    \"\"\"A drawable shape.\"\"\"\n\n    def area(self):\n        '''Returns the area.'''\n        return 0\n\ndef plain():\n    return 1\n");
+
+        let docs: Vec<String> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::DocComment { target, content, line, doc_type, .. } => Some(format!("{:?} {} @ {}: {}", doc_type, target, line, content)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(docs, vec![
+            "Class Shape @ 2: A drawable shape.".to_string(),
+            "Function area @ 5: Returns the area.".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn decorators_name_their_target() {
+        let file_events = parse("@dataclass\nclass Point:\n    x: int = 0\n\n@app.route(\"/\")\n@login_required\ndef index():\n    pass\n");
+
+        let decorators: Vec<(&str, &str, usize)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::PythonDecorator { target, decorator, line, .. } => Some((target.as_str(), decorator.as_str(), *line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(decorators, vec![
+            ("Point", "dataclass", 1),
+            ("index", "app.route(\"/\")", 5),
+            ("index", "login_required", 6),
+        ]);
+    }
+
+    #[test]
+    fn async_def_emits_an_async_function_event() {
+        let file_events = parse("async def fetch(url):\n    return url\n\ndef plain():\n    pass\n");
+
+        let async_functions: Vec<(&str, usize)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::PythonAsyncFunction { function_name, line, .. } => Some((function_name.as_str(), *line)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(async_functions, vec![("fetch", 1)]);
+        assert_eq!(function_names(&file_events), vec!["fetch", "plain"]);
+    }
+
+    #[test]
+    fn for_and_while_loops_are_loop_blocks() {
+        let file_events = parse("for key, value in items.items():\n    print(key)\n\nwhile running:\n    step()\n    step()\n");
+
+        let loops: Vec<String> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::LoopBlock { loop_type, iterator_variable, iterable, start_line, end_line, .. } => {
+                    Some(format!("{} {:?} in {:?} @ {}-{}", loop_type, iterator_variable, iterable, start_line, end_line))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(loops, vec![
+            "for Some(\"key, value\") in Some(\"items.items()\") @ 1-2".to_string(),
+            "while None in None @ 4-6".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn each_if_branch_is_its_own_conditional_block() {
+        let file_events = parse("if x > 0:\n    sign = 1\nelif x < 0:\n    sign = -1\nelse:\n    sign = 0\n");
+
+        let branches: Vec<String> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::ConditionalBlock { condition_type, condition_summary, start_line, end_line, .. } => {
+                    Some(format!("{} {:?} @ {}-{}", condition_type, condition_summary, start_line, end_line))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(branches, vec![
+            "if Some(\"x > 0\") @ 1-2".to_string(),
+            "elif Some(\"x < 0\") @ 3-4".to_string(),
+            "else None @ 5-6".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn try_blocks_collect_caught_types_and_finally() {
+        let file_events = parse("try:\n    load()\nexcept (KeyError, ValueError):\n    pass\nexcept OSError as error:\n    pass\nfinally:\n    close()\n\ntry:\n    load()\nexcept:\n    pass\n");
+
+        let tries: Vec<String> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::TryBlock { start_line, end_line, exception_types, has_finally, .. } => {
+                    Some(format!("{:?} finally={} @ {}-{}", exception_types, has_finally, start_line, end_line))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(tries, vec![
+            "[\"KeyError\", \"ValueError\", \"OSError\"] finally=true @ 1-8".to_string(),
+            "[] finally=false @ 10-13".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn calls_name_their_innermost_enclosing_function() {
+        let file_events = parse("setup()\n\ndef outer():\n    helper(1, key=2)\n\n    def inner():\n        log.info(\"x\")\n\n    finish()\n");
+
+        let calls: Vec<(Option<&str>, &str)> = file_events.function_calls()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionCall { caller_function, callee, .. } => Some((caller_function.as_deref(), callee.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, vec![
+            (None, "setup"),
+            (Some("outer"), "helper"),
+            (Some("inner"), "log.info"),
+            (Some("outer"), "finish"),
+        ]);
+    }
+
+    fn inheritance(file_events: &FileEvents) -> Vec<(String, Vec<String>, usize)> {
+        file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::ClassInheritance { child_class, parent_classes, line, .. } => Some((child_class.clone(), parent_classes.clone(), *line)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn single_and_multiple_inheritance_list_their_parents() {
+        let file_events = parse("class Animal:\n    pass\n\nclass Cat(Animal):\n    pass\n\nclass Dog(Animal, Mixin, metaclass=ABCMeta):\n    pass\n");
+
+        assert_eq!(inheritance(&file_events), vec![
+            ("Cat".to_string(), vec!["Animal".to_string()], 4),
+            ("Dog".to_string(), vec!["Animal".to_string(), "Mixin".to_string()], 7),
+        ]);
+    }
+
+    #[test]
+    fn comprehensions_yield_one_event_per_for_clause() {
+        let file_events = parse("flat = [y for x in rows for y in x]\nsquares = {n: n * n for n in range(3)}\nseen = {w.lower() for w in words}\n");
+
+        let comprehensions: Vec<String> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::PythonListComprehension { result_expression, iterator_variable, iterable, line, .. } => {
+                    Some(format!("{} for {} in {} @ {}", result_expression, iterator_variable, iterable, line))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(comprehensions, vec![
+            "y for x in rows @ 1".to_string(),
+            "y for y in x @ 1".to_string(),
+            "n: n * n for n in range(3) @ 2".to_string(),
+            "w.lower() for w in words @ 3".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn wildcard_import_is_flagged() {
+        let file_events = parse("import sys\nfrom os import *\n");
+
+        assert_eq!(file_events.wildcard_imports().count(), 1);
+        assert_eq!(file_events.diagnostics.len(), 1);
+        assert_eq!(file_events.diagnostics[0].line, 2);
+        assert!(file_events.diagnostics[0].message.contains("os"), "{}", file_events.diagnostics[0].message);
+    }
+
+    #[test]
+    fn each_with_item_is_a_context_manager() {
+        let file_events = parse("with open(path) as handle, lock:\n    pass\n\nasync def run():\n    async with session() as s:\n        pass\n");
+
+        let managers: Vec<(Option<&str>, &str, usize)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::PythonContextManager { variable, context_expression, line, .. } => Some((variable.as_deref(), context_expression.as_str(), *line)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(managers, vec![
+            (Some("handle"), "open(path)", 1),
+            (None, "lock", 1),
+            (Some("s"), "session()", 5),
+        ]);
+    }
+
+    fn class_start_line(file_events: &FileEvents) -> usize {
+        file_events.classes()
+            .find_map(|event| match event {
+                ParseEvent::ClassDefinition { start_line, .. } => Some(*start_line),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn decorated_class_starts_at_its_decorator_when_enabled() {
+        let source = "import attrs\n\n@attrs.define\nclass Point:\n    x: int\n";
+
+        assert_eq!(class_start_line(&parse_with(PythonParser::new().with_decorators_in_span(true), source)), 3);
+        assert_eq!(class_start_line(&parse(source)), 4);
+    }
+
+    fn comment_types(file_events: &FileEvents) -> Vec<CommentType> {
+        file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::Comment { comment_type, .. } => Some(comment_type.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn custom_marker_classifies_comments() {
+        let source = "# HACK: retry until the API stops flaking\n# TODO: remove\n# plain note\n";
+        let hack = CommentType::Custom("HACK".to_string());
+
+        let file_events = parse_with(PythonParser::new().with_comment_marker("HACK", hack.clone()), source);
+
+        assert_eq!(comment_types(&file_events), vec![hack, CommentType::Todo, CommentType::Line]);
+        assert_eq!(comment_types(&parse(source))[0], CommentType::Line);
+    }
+
+    #[test]
+    fn module_doc_returns_the_module_docstring() {
+        let documented = parse("\"\"\"Helpers for loading configs.\n\nSecond paragraph.\"\"\"\n\ndef load():\n    \"\"\"Not the module doc.\"\"\"\n");
+
+        assert_eq!(documented.module_doc(), Some("Helpers for loading configs.\n\nSecond paragraph."));
+        assert_eq!(documented.module_doc_summary(1).as_deref(), Some("Helpers for loading configs."));
+        assert_eq!(parse("def load():\n    \"\"\"Not the module doc.\"\"\"\n").module_doc(), None);
+    }
+
+    #[test]
+    fn del_statement_is_a_delete_access() {
+        let file_events = parse("cache = {}\ndel cache\n");
+
+        let deletions: Vec<(&str, usize)> = file_events.deletions()
+            .filter_map(|event| match event {
+                ParseEvent::VariableAccess { variable, line, .. } => Some((variable.as_str(), *line)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(deletions, vec![("cache", 2)]);
+    }
+
+    fn variables(file_events: &FileEvents) -> Vec<(&str, Option<&str>, usize)> {
+        file_events.variables()
+            .filter_map(|event| match event {
+                ParseEvent::VariableDefinition { name, var_type, line, .. } => Some((name.as_str(), var_type.as_deref(), *line)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chained_and_tuple_assignments_define_every_target() {
+        let file_events = parse("a = b = 0\nx, y = f()\nfirst, *rest = items\n");
+
+        assert_eq!(variables(&file_events), vec![
+            ("a", None, 1),
+            ("b", None, 1),
+            ("x", None, 2),
+            ("y", None, 2),
+            ("first", None, 3),
+            ("rest", Some("list"), 3),
+        ]);
+    }
+
+    #[test]
+    fn class_and_instance_attributes_are_kept_apart() {
+        let file_events = parse("class Counter:\n    step = 1\n    limit: int = 10\n\n    def __init__(self):\n        self.count = 0\n        self.history = []\n\n    def bump(self):\n        self.last = 1\n");
+
+        assert_eq!(file_events.class_fields("Counter"), vec!["step", "limit"]);
+        assert_eq!(file_events.instance_fields("Counter"), vec!["count", "history"]);
+    }
+
+    #[test]
+    fn incomplete_signature_keeps_parameter_names() {
+        let file_events = parse("def broken(a: , b: int = , c=):\n    pass\n\ndef after():\n    pass\n");
+
+        assert!(function_names(&file_events).contains(&"after"));
+        let names: Vec<String> = file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, .. } if name == "broken" => {
+                    Some(parameters_detailed.iter().map(|parameter| parameter.name.clone()).collect::<Vec<_>>())
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn broken_def_header_parses_without_panicking() {
+        let source = "def (:\n    pass\n\ndef after():\n    pass\n";
+        let file = tempfile::Builder::new().suffix(".py").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+
+        let file_events = PythonParser::new().parse_file(source, file.path()).expect("a broken header is still Ok");
+
+        assert_eq!(function_names(&file_events), vec!["after"]);
+    }
+}
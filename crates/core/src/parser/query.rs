@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use tree_sitter::{Language, Node, Query, QueryCursor};
+
+use crate::parser::{event::{FileEvents, ParseEvent}, r#trait::LanguageParser};
+
+// Turns the captures of one query match into an event, or None to drop the match.
+pub type CaptureMapper = Box<dyn Fn(&Captures) -> Option<ParseEvent> + Send + Sync>;
+
+// The nodes of a single query match, looked up by capture name (without the leading `@`).
+pub struct Captures<'a> {
+    nodes: HashMap<&'a str, Node<'a>>,
+    source_code: &'a str,
+}
+
+impl<'a> Captures<'a> {
+    pub fn node(&self, capture: &str) -> Option<Node<'a>> {
+        self.nodes.get(capture).copied()
+    }
+
+    pub fn text(&self, capture: &str) -> Option<&'a str> {
+        self.node(capture)
+            .and_then(|node| node.utf8_text(self.source_code.as_bytes()).ok())
+    }
+
+    // 1-based, like the lines the hand-written parsers report.
+    pub fn line(&self, capture: &str) -> Option<usize> {
+        self.node(capture).map(|node| node.start_position().row + 1)
+    }
+
+    pub fn column(&self, capture: &str) -> Option<usize> {
+        self.node(capture).map(|node| node.start_position().column + 1)
+    }
+}
+
+// A parser described by tree-sitter queries instead of a hand-written walk: every match of a
+// registered query is handed to its mapper, and whatever events the mappers return are recorded.
+pub struct QueryBasedParser {
+    language: Language,
+    language_name: &'static str,
+    file_extensions: Vec<&'static str>,
+    queries: Vec<(Query, CaptureMapper)>,
+}
+
+impl QueryBasedParser {
+    pub fn new(language: Language, language_name: &'static str, file_extensions: Vec<&'static str>) -> Self {
+        Self {
+            language,
+            language_name,
+            file_extensions,
+            queries: Vec::new(),
+        }
+    }
+
+    // Fails if `source` isn't a valid query for this parser's grammar.
+    pub fn with_query<F>(mut self, source: &str, mapper: F) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        F: Fn(&Captures) -> Option<ParseEvent> + Send + Sync + 'static,
+    {
+        let query = Query::new(self.language, source)?;
+        self.queries.push((query, Box::new(mapper)));
+        Ok(self)
+    }
+}
+
+impl LanguageParser for QueryBasedParser {
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn language_name(&self) -> &'static str {
+        self.language_name
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &self.file_extensions
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        for (query, mapper) in &self.queries {
+            let capture_names = query.capture_names();
+            let mut cursor = QueryCursor::new();
+
+            for query_match in cursor.matches(query, *node, source_code.as_bytes()) {
+                let captures = Captures {
+                    nodes: query_match.captures.iter()
+                        .map(|capture| (capture_names[capture.index as usize].as_str(), capture.node))
+                        .collect(),
+                    source_code,
+                };
+
+                if let Some(event) = mapper(&captures) {
+                    file_events.add_event(event);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{event::Visibility, python::PythonParser};
+
+    fn python_function_parser() -> QueryBasedParser {
+        QueryBasedParser::new(tree_sitter_python::language(), "python", vec!["py"])
+            .with_query("(function_definition name: (identifier) @name) @function", |captures| {
+                Some(ParseEvent::FunctionDefinition {
+                    name: captures.text("name")?.to_string(),
+                    start_line: captures.line("function")?,
+                    start_column: captures.column("function")?,
+                    end_line: captures.node("function")?.end_position().row + 1,
+                    parameters: Vec::new(),
+                    parameters_detailed: Vec::new(),
+                    return_type: None,
+                    visibility: Visibility::Public,
+                    is_generator: false,
+                    is_async_generator: false,
+                    snippet: None,
+                })
+            })
+            .unwrap()
+    }
+
+    fn function_spans(file_events: &FileEvents) -> Vec<(String, usize, usize)> {
+        let mut spans: Vec<(String, usize, usize)> = file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, start_line, end_line, .. } => Some((name.clone(), *start_line, *end_line)),
+                _ => None,
+            })
+            .collect();
+        spans.sort();
+        spans
+    }
+
+    #[test]
+    fn function_query_matches_the_hand_written_python_parser() {
+        let source = "def outer():\n    def inner():\n        pass\n\nclass Shape:\n    def area(self):\n        return 0\n\nasync def fetch():\n    pass\n";
+        let file = tempfile::Builder::new().suffix(".py").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+
+        let from_query = python_function_parser().parse_file(source, file.path()).unwrap();
+        let hand_written = PythonParser::new().parse_file(source, file.path()).unwrap();
+
+        assert_eq!(function_spans(&from_query).len(), 4);
+        assert_eq!(function_spans(&from_query), function_spans(&hand_written));
+    }
+
+    #[test]
+    fn invalid_query_is_rejected() {
+        let parser = QueryBasedParser::new(tree_sitter_python::language(), "python", vec!["py"]);
+        assert!(parser.with_query("(not_a_node_kind) @capture", |_| None).is_err());
+    }
+}
@@ -1,13 +1,26 @@
 use std::collections::HashMap;
 use std::path::Path;
 
-use crate::parser::{event::FileEvents, python::PythonParser};
+use crate::error::CortexError;
+use crate::extension_filter::{compound_extension, ExtensionFilter};
+use crate::parser::{event::{CommentType, FileEvents}, golang::GoParser, ipynb::IpynbParser, java::JavaParser, javascript::JavaScriptParser, python::PythonParser, rust::RustParser, typescript::TypeScriptParser};
 
-use super::r#trait::{LanguageParser};
+use super::r#trait::{IncrementalParse, LanguageParser, PreviousParse};
+
+pub const DEFAULT_MAX_FUNCTION_LINES: usize = 100;
 
 pub struct LanguageParserRegistry {
     parsers: HashMap<String, Box<dyn LanguageParser>>,
     extension_to_language: HashMap<String, String>,
+    record_snippets: bool,
+    max_function_lines: usize,
+    normalize_order: bool,
+}
+
+impl Default for LanguageParserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl LanguageParserRegistry {
@@ -15,15 +28,28 @@ impl LanguageParserRegistry {
         let mut registry = Self {
             parsers: HashMap::new(),
             extension_to_language: HashMap::new(),
+            record_snippets: false,
+            max_function_lines: DEFAULT_MAX_FUNCTION_LINES,
+            normalize_order: false,
         };
         
         // Register built-in parsers
-        registry.register_parser(Box::new(PythonParser));
+        registry.register_parser(Box::new(PythonParser::new()));
+        registry.register_parser(Box::new(IpynbParser::new()));
+        registry.register_parser(Box::new(RustParser::new()));
+        registry.register_parser(Box::new(JavaScriptParser::new()));
+        registry.register_parser(Box::new(TypeScriptParser::new()));
+        registry.register_parser(Box::new(GoParser::new()));
+        registry.register_parser(Box::new(JavaParser::new()));
         
         registry
     }
     
-    pub fn register_parser(&mut self, parser: Box<dyn LanguageParser>) {
+    pub fn register_parser(&mut self, mut parser: Box<dyn LanguageParser>) {
+        if self.record_snippets {
+            parser.set_record_snippets(true);
+        }
+
         let language_name = parser.language_name().to_string();
         
         for &ext in parser.file_extensions() {
@@ -33,17 +59,136 @@ impl LanguageParserRegistry {
         self.parsers.insert(language_name, parser);
     }
     
-    pub fn get_parser_for_file(&self, file_path: &Path) -> Option<&Box<dyn LanguageParser>> {
-        let extension = file_path.extension()?.to_str()?;
+    pub fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+        for parser in self.parsers.values_mut() {
+            parser.set_record_snippets(enabled);
+        }
+    }
+    
+    // Markers are per language; a language without a registered parser is ignored.
+    pub fn set_comment_marker(&mut self, language: &str, marker: &str, comment_type: CommentType) {
+        if let Some(parser) = self.parsers.get_mut(language) {
+            parser.set_comment_marker(marker, comment_type);
+        }
+    }
+    
+    pub fn set_max_function_lines(&mut self, max_lines: usize) {
+        self.max_function_lines = max_lines;
+    }
+    
+    pub fn set_normalize_order(&mut self, enabled: bool) {
+        self.normalize_order = enabled;
+    }
+    
+    // A parser registered for a compound extension (`d.ts`) wins over one for the final suffix (`ts`).
+    pub fn get_parser_for_file(&self, file_path: &Path) -> Option<&dyn LanguageParser> {
+        let compound_language = compound_extension(file_path)
+            .and_then(|extension| self.extension_to_language.get(&extension));
+        let language = match compound_language {
+            Some(language) => language,
+            None => self.extension_to_language.get(file_path.extension()?.to_str()?)?,
+        };
+        self.parsers.get(language).map(|parser| parser.as_ref())
+    }
+    
+    // Extensionless scripts are parsed by the language their shebang names.
+    fn get_parser_for_shebang(&self, content: &str) -> Option<&dyn LanguageParser> {
+        let extension = ExtensionFilter::shebang_extension(content.lines().next()?)?;
         let language = self.extension_to_language.get(extension)?;
-        self.parsers.get(language)
+        self.parsers.get(language).map(|parser| parser.as_ref())
     }
     
     pub fn parse_file(&self, file_path: &Path, content: &str) -> Result<Option<FileEvents>, Box<dyn std::error::Error>> {
+        let parser = self.get_parser_for_file(file_path)
+            .or_else(|| file_path.extension().is_none().then(|| self.get_parser_for_shebang(content)).flatten());
+        if let Some(parser) = parser {
+            let mut file_events = parser.parse_file(content, file_path)?;
+            self.post_process(&mut file_events);
+            Ok(Some(file_events))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    // Events come back unprocessed, since they seed the splice of the next reparse; `post_process` a copy before
+    // handing them out.
+    pub fn parse_file_incremental(&self, file_path: &Path, content: &str, previous: Option<PreviousParse>) -> Result<Option<IncrementalParse>, Box<dyn std::error::Error>> {
+        let parser = self.get_parser_for_file(file_path)
+            .or_else(|| file_path.extension().is_none().then(|| self.get_parser_for_shebang(content)).flatten());
+        if let Some(parser) = parser {
+            Ok(Some(parser.parse_file_incremental(content, file_path, previous)?))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    pub fn parse_file_streamed(&self, file_path: &Path, content: &[u8], chunk_size: usize) -> Result<Option<FileEvents>, Box<dyn std::error::Error>> {
         if let Some(parser) = self.get_parser_for_file(file_path) {
-            Ok(Some(parser.parse_file(content, file_path)?))
+            let mut file_events = parser.parse_streamed(content, file_path, chunk_size)?;
+            self.post_process(&mut file_events);
+            Ok(Some(file_events))
         } else {
             Ok(None)
         }
     }
-}
\ No newline at end of file
+    
+    // A dry run for pre-commit style checks: whether `content` parses without syntax errors.
+    pub fn validate(&self, file_path: &Path, content: &str) -> Result<bool, CortexError> {
+        let parser = self.get_parser_for_file(file_path)
+            .ok_or_else(|| CortexError::UnsupportedFile { path: file_path.to_path_buf() })?;
+        parser.validate(content, file_path)
+            .map_err(|e| CortexError::ParseFailed { path: file_path.to_path_buf(), reason: e.to_string() })
+    }
+    
+    pub fn parse_file_as(&self, file_path: &Path, content: &str, language: &str) -> Result<FileEvents, Box<dyn std::error::Error>> {
+        let parser = self.parsers.get(language)
+            .ok_or_else(|| format!("No parser registered for language: {}", language))?;
+        let mut file_events = parser.parse_file(content, file_path)?;
+        self.post_process(&mut file_events);
+        Ok(file_events)
+    }
+    
+    pub fn post_process(&self, file_events: &mut FileEvents) {
+        file_events.check_function_lengths(self.max_function_lines);
+        if self.normalize_order {
+            file_events.sort_by_position();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::event::{Language, ParseEvent};
+
+    fn write_temp(suffix: &str, content: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        std::fs::write(file.path(), content).unwrap();
+        file
+    }
+
+    #[test]
+    fn parse_file_as_overrides_the_extension() {
+        let registry = LanguageParserRegistry::new();
+        let source = "def hello():\n    pass\n";
+        let file = write_temp(".txt", source);
+
+        let file_events = registry.parse_file_as(file.path(), source, "python").unwrap();
+
+        assert_eq!(file_events.language, Language::Python);
+        assert!(matches!(file_events.functions().next(), Some(ParseEvent::FunctionDefinition { name, .. }) if name == "hello"));
+        assert!(registry.parse_file_as(file.path(), source, "cobol").is_err());
+    }
+
+    #[test]
+    fn validate_reports_syntax_errors() {
+        let registry = LanguageParserRegistry::new();
+        let valid = write_temp(".py", "def ok():\n    return 1\n");
+        let broken = write_temp(".py", "def broken(:\n    return\n");
+
+        assert!(registry.validate(valid.path(), "def ok():\n    return 1\n").unwrap());
+        assert!(!registry.validate(broken.path(), "def broken(:\n    return\n").unwrap());
+        assert!(matches!(registry.validate(Path::new("notes.txt"), ""), Err(CortexError::UnsupportedFile { .. })));
+    }
+}
@@ -0,0 +1,463 @@
+use tree_sitter::{Language, Node, TreeCursor};
+use tree_sitter_rust::language as rust_language;
+
+use crate::parser::{event::{self, FileEvents, Parameter, ParameterKind, ParseEvent, Visibility}, r#trait::LanguageParser};
+
+#[derive(Default)]
+pub struct RustParser {
+    record_snippets: bool,
+}
+
+impl LanguageParser for RustParser {
+    fn language(&self) -> Language {
+        rust_language()
+    }
+
+    fn language_name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::Rust
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["rs"]
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.record_snippets = enabled;
+    }
+
+    // Only a bare `pub` is public API; `pub(crate)` stays inside the crate and `pub(super)`/`pub(in ..)` inside a module.
+    fn symbol_visibility(&self, node: &Node, _name: &str, source_code: &str) -> Visibility {
+        let mut cursor: TreeCursor = node.walk();
+        let modifier = node.children(&mut cursor)
+            .find(|child| child.kind() == "visibility_modifier")
+            .map(|modifier| self.node_text(modifier, source_code).split_whitespace().collect::<String>());
+
+        match modifier.as_deref() {
+            Some("pub") => Visibility::Public,
+            Some("pub(crate)") => Visibility::Crate,
+            Some(_) => Visibility::Module,
+            None => Visibility::Private,
+        }
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.walk_scoped(node, source_code, file_events, &mut Vec::new())
+    }
+}
+
+impl RustParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.record_snippets = enabled;
+        self
+    }
+
+    // `scope` holds the names of the enclosing functions, innermost last, so calls can name their caller.
+    fn walk_scoped(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let should_parse_children: bool = self.parse_node(node, source_code, file_events, scope)?;
+
+        if should_parse_children {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.walk_scoped(&child, source_code, file_events, scope)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_node(&self, node: &Node, source_code: &str, file_events: &mut FileEvents, scope: &mut Vec<String>) -> Result<bool, Box<dyn std::error::Error>> {
+        match node.kind() {
+            "function_item" | "function_signature_item" => {
+                let Some(function_event) = self.parse_function(node, source_code) else {
+                    return Ok(false);
+                };
+                let ParseEvent::FunctionDefinition { name, .. } = &function_event else {
+                    return Ok(false);
+                };
+                let name = name.clone();
+                file_events.add_event(function_event);
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    scope.push(name);
+                    self.walk_scoped(&body, source_code, file_events, scope)?;
+                    scope.pop();
+                }
+
+                Ok(false)
+            }
+            "struct_item" | "enum_item" | "union_item" => {
+                if let Some(class_event) = self.parse_type_definition(node, source_code) {
+                    file_events.add_event(class_event);
+                }
+                Ok(false)
+            }
+            "use_declaration" => {
+                for import_event in self.parse_use(node, source_code) {
+                    file_events.add_event(import_event);
+                }
+                Ok(false)
+            }
+            "macro_definition" => {
+                if let Some(macro_event) = self.parse_macro_definition(node, source_code) {
+                    file_events.add_event(macro_event);
+                }
+                Ok(false)
+            }
+            "macro_invocation" => {
+                if let Some(macro_name) = node.child_by_field_name("macro") {
+                    file_events.add_event(ParseEvent::FunctionCall {
+                        caller_function: scope.last().cloned(),
+                        callee: format!("{}!", self.node_text(macro_name, source_code)),
+                        line: node.start_position().row + 1,
+                        column: node.start_position().column + 1,
+                        arguments: Vec::new(),
+                    });
+                }
+                Ok(false)
+            }
+            "call_expression" => {
+                if let Some(call_event) = self.parse_call(node, source_code, scope) {
+                    file_events.add_event(call_event);
+                }
+                Ok(true)
+            }
+            "line_comment" | "block_comment" => Ok(false),
+            _ => Ok(true),
+        }
+    }
+
+    fn parse_function(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+        let (parameters, parameters_detailed) = node.child_by_field_name("parameters")
+            .map(|params_node| self.extract_parameters(&params_node, source_code))
+            .unwrap_or_default();
+        let return_type = node.child_by_field_name("return_type")
+            .map(|return_type| self.node_text(return_type, source_code).to_string());
+
+        Some(ParseEvent::FunctionDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            parameters,
+            parameters_detailed,
+            return_type,
+            is_generator: false,
+            is_async_generator: false,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // Structs, enums and unions all map onto ClassDefinition; enum variants are reported as its fields.
+    fn parse_type_definition(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor: TreeCursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                if matches!(member.kind(), "field_declaration" | "enum_variant")
+                    && let Some(member_name) = member.child_by_field_name("name") {
+                    fields.push(self.node_text(member_name, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::ClassDefinition {
+            visibility: self.symbol_visibility(node, &name, source_code),
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            fields,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    // A use tree can name several modules (`use a::{b::C, d::*}`), so imports are grouped per module path.
+    fn parse_use(&self, node: &Node, source_code: &str) -> Vec<ParseEvent> {
+        let Some(argument) = node.child_by_field_name("argument") else {
+            return Vec::new();
+        };
+
+        let mut imported: Vec<(String, Option<String>, bool)> = Vec::new();
+        self.collect_use_paths(&argument, "", source_code, &mut imported);
+
+        let line = node.start_position().row + 1;
+        let column = node.start_position().column + 1;
+        let mut imports: Vec<ParseEvent> = Vec::new();
+
+        for (module, item, wildcard) in imported {
+            let existing = imports.iter_mut().find(|event| matches!(event,
+                ParseEvent::ImportStatement { module: existing, .. } if *existing == module));
+
+            match existing {
+                Some(ParseEvent::ImportStatement { items, is_wildcard, .. }) => {
+                    items.extend(item);
+                    *is_wildcard |= wildcard;
+                }
+                _ => imports.push(ParseEvent::ImportStatement {
+                    module,
+                    items: item.into_iter().collect(),
+                    line,
+                    column,
+                    is_wildcard: wildcard,
+                }),
+            }
+        }
+
+        imports
+    }
+
+    fn collect_use_paths(&self, node: &Node, prefix: &str, source_code: &str, imported: &mut Vec<(String, Option<String>, bool)>) {
+        let join = |path: &str| if prefix.is_empty() { path.to_string() } else { format!("{}::{}", prefix, path) };
+
+        match node.kind() {
+            "scoped_identifier" => {
+                let module = node.child_by_field_name("path")
+                    .map(|path| join(self.node_text(path, source_code)))
+                    .unwrap_or_else(|| prefix.to_string());
+                let item = node.child_by_field_name("name")
+                    .map(|name| self.node_text(name, source_code).to_string());
+                imported.push((module, item, false));
+            }
+            "use_as_clause" => {
+                if let Some(path) = node.child_by_field_name("path") {
+                    self.collect_use_paths(&path, prefix, source_code, imported);
+                }
+            }
+            "scoped_use_list" => {
+                let nested_prefix = node.child_by_field_name("path")
+                    .map(|path| join(self.node_text(path, source_code)))
+                    .unwrap_or_else(|| prefix.to_string());
+                if let Some(list) = node.child_by_field_name("list") {
+                    self.collect_use_paths(&list, &nested_prefix, source_code, imported);
+                }
+            }
+            "use_list" => {
+                let mut cursor: TreeCursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.collect_use_paths(&child, prefix, source_code, imported);
+                }
+            }
+            "use_wildcard" => {
+                let module = node.named_child(0)
+                    .map(|path| join(self.node_text(path, source_code)))
+                    .unwrap_or_else(|| prefix.to_string());
+                imported.push((module, None, true));
+            }
+            "line_comment" | "block_comment" => {}
+            _ if prefix.is_empty() => imported.push((self.node_text(*node, source_code).to_string(), None, false)),
+            _ => imported.push((prefix.to_string(), Some(self.node_text(*node, source_code).to_string()), false)),
+        }
+    }
+
+    fn parse_macro_definition(&self, node: &Node, source_code: &str) -> Option<ParseEvent> {
+        let name = self.node_text(node.child_by_field_name("name")?, source_code).to_string();
+
+        Some(ParseEvent::MacroDefinition {
+            name,
+            start_line: node.start_position().row + 1,
+            start_column: node.start_position().column + 1,
+            end_line: node.end_position().row + 1,
+            snippet: self.snippet(node, source_code),
+        })
+    }
+
+    fn parse_call(&self, node: &Node, source_code: &str, scope: &[String]) -> Option<ParseEvent> {
+        let callee = node.child_by_field_name("function")?;
+
+        let mut arguments: Vec<String> = Vec::new();
+        if let Some(args_node) = node.child_by_field_name("arguments") {
+            let mut cursor: TreeCursor = args_node.walk();
+            for argument in args_node.named_children(&mut cursor) {
+                if !matches!(argument.kind(), "line_comment" | "block_comment") {
+                    arguments.push(self.node_text(argument, source_code).to_string());
+                }
+            }
+        }
+
+        Some(ParseEvent::FunctionCall {
+            caller_function: scope.last().cloned(),
+            callee: self.node_text(callee, source_code).to_string(),
+            line: node.start_position().row + 1,
+            column: node.start_position().column + 1,
+            arguments,
+        })
+    }
+
+    //Helper functions
+
+    fn extract_parameters(&self, params_node: &Node, source_code: &str) -> (Vec<String>, Vec<Parameter>) {
+        let mut parameters: Vec<String> = Vec::new();
+        let mut parameters_detailed: Vec<Parameter> = Vec::new();
+        let mut cursor: TreeCursor = params_node.walk();
+
+        for child in params_node.named_children(&mut cursor) {
+            match child.kind() {
+                "self_parameter" => {
+                    parameters.push(self.node_text(child, source_code).to_string());
+                    parameters_detailed.push(Parameter {
+                        name: "self".to_string(),
+                        param_type: None,
+                        default_value: None,
+                        kind: ParameterKind::Positional,
+                    });
+                }
+                "parameter" => {
+                    parameters.push(self.node_text(child, source_code).to_string());
+                    if let Some(pattern) = child.child_by_field_name("pattern") {
+                        parameters_detailed.push(Parameter {
+                            name: self.node_text(pattern, source_code).to_string(),
+                            param_type: child.child_by_field_name("type")
+                                .map(|param_type| self.node_text(param_type, source_code).to_string()),
+                            default_value: None,
+                            kind: ParameterKind::Positional,
+                        });
+                    }
+                }
+                "variadic_parameter" => {
+                    parameters.push(self.node_text(child, source_code).to_string());
+                    parameters_detailed.push(Parameter {
+                        name: "...".to_string(),
+                        param_type: None,
+                        default_value: None,
+                        kind: ParameterKind::VarPositional,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        (parameters, parameters_detailed)
+    }
+
+    fn snippet(&self, node: &Node, source_code: &str) -> Option<String> {
+        self.record_snippets.then(|| self.node_text(*node, source_code).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(".rs").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        RustParser::new().parse_file(source, file.path()).unwrap()
+    }
+
+    #[test]
+    fn macro_rules_emits_one_macro_definition() {
+        let file_events = parse("macro_rules! foo {}\n");
+
+        let macros: Vec<&str> = file_events.macros()
+            .filter_map(|event| match event {
+                ParseEvent::MacroDefinition { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(macros, vec!["foo"]);
+        assert_eq!(file_events.functions().count(), 0);
+    }
+
+    #[test]
+    fn macro_invocation_is_a_call() {
+        let file_events = parse("fn main() {\n    println!(\"hi\");\n}\n");
+
+        let calls: Vec<(Option<&str>, &str)> = file_events.function_calls()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionCall { caller_function, callee, .. } => Some((caller_function.as_deref(), callee.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(calls, vec![(Some("main"), "println!")]);
+    }
+
+    #[test]
+    fn functions_and_methods_with_parameters_and_return_type() {
+        let file_events = parse("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nimpl Shape {\n    fn area(&self) -> f64 {\n        0.0\n    }\n}\n");
+
+        // Rendered as `name(parameter: type, ...) -> return type @ start-end`.
+        let functions: Vec<String> = file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, parameters_detailed, return_type, start_line, end_line, .. } => {
+                    let parameters: Vec<String> = parameters_detailed.iter()
+                        .map(|parameter| format!("{}: {}", parameter.name, parameter.param_type.as_deref().unwrap_or("_")))
+                        .collect();
+                    Some(format!("{}({}) -> {} @ {}-{}", name, parameters.join(", "), return_type.as_deref().unwrap_or("()"), start_line, end_line))
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(functions, vec!["add(a: i32, b: i32) -> i32 @ 1-3", "area(self: _) -> f64 @ 6-8"]);
+    }
+
+    #[test]
+    fn structs_enums_and_unions_are_class_definitions() {
+        let file_events = parse("struct Point {\n    x: f64,\n    y: f64,\n}\n\nenum Shape {\n    Circle,\n    Square,\n}\n\nunion Bits {\n    int: u32,\n    float: f32,\n}\n");
+
+        let classes: Vec<(&str, Vec<&str>)> = file_events.classes()
+            .filter_map(|event| match event {
+                ParseEvent::ClassDefinition { name, fields, .. } => Some((name.as_str(), fields.iter().map(String::as_str).collect())),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(classes, vec![
+            ("Point", vec!["x", "y"]),
+            ("Shape", vec!["Circle", "Square"]),
+            ("Bits", vec!["int", "float"]),
+        ]);
+    }
+
+    #[test]
+    fn use_trees_group_items_by_module() {
+        let file_events = parse("use std::collections::{HashMap, hash_map::Entry};\nuse crate::parser::*;\nuse serde;\nuse std::io::Write as _;\n");
+
+        let imports: Vec<(&str, Vec<&str>, bool, usize)> = file_events.imports()
+            .filter_map(|event| match event {
+                ParseEvent::ImportStatement { module, items, is_wildcard, line, .. } => Some((module.as_str(), items.iter().map(String::as_str).collect(), *is_wildcard, *line)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(imports, vec![
+            ("std::collections", vec!["HashMap"], false, 1),
+            ("std::collections::hash_map", vec!["Entry"], false, 1),
+            ("crate::parser", vec![], true, 2),
+            ("serde", vec![], false, 3),
+            ("std::io", vec!["Write"], false, 4),
+        ]);
+    }
+
+    #[test]
+    fn visibility_follows_the_pub_modifier() {
+        let file_events = parse("pub fn open() {}\npub(crate) fn shared() {}\npub(super) fn parent_only() {}\nfn hidden() {}\npub struct Config {}\n");
+
+        let visibilities: Vec<(&str, Visibility)> = file_events.events.iter()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, visibility, .. } | ParseEvent::ClassDefinition { name, visibility, .. } => Some((name.as_str(), *visibility)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(visibilities, vec![
+            ("open", Visibility::Public),
+            ("shared", Visibility::Crate),
+            ("parent_only", Visibility::Module),
+            ("hidden", Visibility::Private),
+            ("Config", Visibility::Public),
+        ]);
+    }
+}
@@ -1,37 +1,194 @@
-use tree_sitter::{Language, Parser, Node};
+use tree_sitter::{InputEdit, Language, Parser, Node, Tree};
 use std::path::{Path};
 
-use crate::parser::event::{FileEvents};
+use crate::parser::event::{self, CommentType, FileEvents, Visibility};
 
 
-pub trait LanguageParser {
+// A parse's events together with the tree to reuse for the next incremental reparse, if any.
+pub type IncrementalParse = (FileEvents, Option<Tree>);
+
+// The last parse of a file: its tree, already edited to match the new content, the edit itself (None when the
+// content is unchanged), and the events that parse produced.
+pub struct PreviousParse<'a> {
+    pub tree: &'a Tree,
+    pub edit: Option<InputEdit>,
+    pub events: &'a FileEvents,
+}
+
+pub trait LanguageParser: Send + Sync {
     fn language(&self) -> Language;
     fn language_name(&self) -> &'static str;
     fn file_extensions(&self) -> &[&'static str];
     
+    fn language_kind(&self) -> event::Language {
+        event::Language::from(self.language_name())
+    }
+    
+    // Parsers whose extensions need different grammars (e.g. `.ts` and `.tsx`) pick one per file.
+    fn language_for(&self, _file_path: &Path) -> Language {
+        self.language()
+    }
+    
     fn parse_file(&self, content: &str, file_path: &Path) -> Result<FileEvents, Box<dyn std::error::Error>> {
         let mut parser = Parser::new();
-        parser.set_language(self.language())?;
+        parser.set_language(self.language_for(file_path))?;
         
         let tree = parser.parse(content, None)
             .ok_or("Failed to parse file")?;
         
+        self.events_from_tree(&tree, content, file_path)
+    }
+    
+    // tree-sitter reuses the unchanged subtrees of `previous`, and only the top-level nodes the edit touched are
+    // walked again. The new tree is handed back for the next reparse, or None if it can't be reused.
+    fn parse_file_incremental(&self, content: &str, file_path: &Path, previous: Option<PreviousParse>) -> Result<IncrementalParse, Box<dyn std::error::Error>> {
+        let mut parser = Parser::new();
+        parser.set_language(self.language_for(file_path))?;
+        
+        let tree = parser.parse(content, previous.as_ref().map(|previous| previous.tree))
+            .ok_or("Failed to parse file")?;
+        
+        let spliced = match &previous {
+            Some(previous) => self.splice_changed(previous, &tree, content, file_path)?,
+            None => None,
+        };
+        let file_events = match spliced {
+            Some(file_events) => file_events,
+            None => self.events_from_tree(&tree, content, file_path)?,
+        };
+        Ok((file_events, Some(tree)))
+    }
+    
+    // Top-level node kinds whose events come from their own subtree alone, so an edit confined to them can be
+    // re-walked without the rest of the file. Empty means every reparse walks the whole tree.
+    fn splice_kinds(&self) -> &[&'static str] {
+        &[]
+    }
+    
+    // None whenever the edit reaches anything outside `splice_kinds`, and the caller walks the whole tree instead.
+    // The first top-level node is never spliced, since it can carry file-level meaning (a module docstring).
+    fn splice_changed(&self, previous: &PreviousParse, tree: &Tree, content: &str, file_path: &Path) -> Result<Option<FileEvents>, Box<dyn std::error::Error>> {
+        let splice_kinds = self.splice_kinds();
+        if splice_kinds.is_empty() {
+            return Ok(None);
+        }
+        
+        // Rows of the new content that were edited or whose syntax changed, widened to whole top-level nodes.
+        let mut rows = previous.edit.map(|edit| (edit.start_position.row, edit.new_end_position.row));
+        for range in previous.tree.changed_ranges(tree) {
+            let (start, end) = rows.unwrap_or((range.start_point.row, range.end_point.row));
+            rows = Some((start.min(range.start_point.row), end.max(range.end_point.row)));
+        }
+        
+        let mut replacement = FileEvents::new(file_path.to_path_buf(), self.language_kind(), std::fs::metadata(file_path)?.modified()?);
+        replacement.line_count = content.lines().count();
+        
+        let Some(mut rows) = rows else {
+            let mut file_events = previous.events.clone();
+            file_events.last_modified = replacement.last_modified;
+            file_events.parse_timestamp = replacement.parse_timestamp;
+            return Ok(Some(file_events));
+        };
+        
+        let overlaps = |node: &Node, (start, end): (usize, usize)| node.start_position().row <= end && node.end_position().row >= start;
+        let children = top_level_nodes(tree.root_node());
+        loop {
+            let widened = children.iter()
+                .filter(|child| overlaps(child, rows))
+                .fold(rows, |(start, end), child| (start.min(child.start_position().row), end.max(child.end_position().row)));
+            if widened == rows {
+                break;
+            }
+            rows = widened;
+        }
+        let touched: Vec<&Node> = children.iter().filter(|child| overlaps(child, rows)).collect();
+        
+        let old_children = top_level_nodes(previous.tree.root_node());
+        let mut old_touched = old_children.iter().filter(|child| child.has_changes() || overlaps(child, rows));
+        let reaches_first = |nodes: &[Node]| nodes.first().is_some_and(|first| first.has_changes() || first.start_position().row >= rows.0);
+        if reaches_first(&children) || reaches_first(&old_children)
+            || !touched.iter().all(|child| splice_kinds.contains(&child.kind()))
+            || !old_touched.all(|child| splice_kinds.contains(&child.kind())) {
+            return Ok(None);
+        }
+        
+        for child in touched {
+            self.walk_tree(child, content, &mut replacement)?;
+        }
+        
+        let line_delta = previous.edit
+            .map_or(0, |edit| edit.new_end_position.row as isize - edit.old_end_position.row as isize);
+        let old_end_row = rows.1.saturating_add_signed(-line_delta);
+        let mut file_events = previous.events.clone();
+        file_events.splice(rows.0 + 1, old_end_row + 1, line_delta, replacement);
+        Ok(Some(file_events))
+    }
+    
+    // Feeds tree-sitter fixed-size slices of `content` (usually a memory map) rather than one
+    // owned string, so huge generated files never get copied onto the heap.
+    fn parse_streamed(&self, content: &[u8], file_path: &Path, chunk_size: usize) -> Result<FileEvents, Box<dyn std::error::Error>> {
+        let mut parser = Parser::new();
+        parser.set_language(self.language_for(file_path))?;
+        
+        let tree = parser.parse_with(&mut |offset, _| {
+            let end = content.len().min(offset + chunk_size);
+            &content[offset.min(end)..end]
+        }, None)
+            .ok_or("Failed to parse file")?;
+        
+        let source_code = std::str::from_utf8(content)?;
+        self.events_from_tree(&tree, source_code, file_path)
+    }
+    
+    // Only checks that the source is free of syntax errors; no events are extracted.
+    fn validate(&self, content: &str, file_path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut parser = Parser::new();
+        parser.set_language(self.language_for(file_path))?;
+        
+        let tree = parser.parse(content, None)
+            .ok_or("Failed to parse file")?;
+        
+        Ok(!tree.root_node().has_error())
+    }
+    
+    fn events_from_tree(&self, tree: &Tree, content: &str, file_path: &Path) -> Result<FileEvents, Box<dyn std::error::Error>> {
         let metadata = std::fs::metadata(file_path)?;
         let last_modified = metadata.modified()?;
         
         let mut file_events = FileEvents::new(
             file_path.to_path_buf(),
-            self.language_name().to_string(),
+            self.language_kind(),
             last_modified,
         );
+        file_events.line_count = content.lines().count();
         
         self.walk_tree(&tree.root_node(), content, &mut file_events)?;
         Ok(file_events)
     }
     
+    fn set_record_snippets(&mut self, _enabled: bool) {}
+    
+    // Python's convention by default: `__name` is mangled to its class, `_name` is internal to its module.
+    fn symbol_visibility(&self, _node: &Node, name: &str, _source_code: &str) -> Visibility {
+        if name.starts_with("__") && !name.ends_with("__") {
+            Visibility::Private
+        } else if name.starts_with('_') {
+            Visibility::Module
+        } else {
+            Visibility::Public
+        }
+    }
+    
+    fn set_comment_marker(&mut self, _marker: &str, _comment_type: CommentType) {}
+    
     fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>>;
     
     fn node_text<'a>(&self, node: Node, source_code: &'a str) -> &'a str {
         &source_code[node.byte_range()]
     }
-}
\ No newline at end of file
+}
+
+fn top_level_nodes(root: Node) -> Vec<Node> {
+    let mut cursor = root.walk();
+    root.children(&mut cursor).collect()
+}
@@ -0,0 +1,121 @@
+use std::path::Path;
+use tree_sitter::{Language, Node};
+use tree_sitter_typescript::{language_tsx, language_typescript};
+
+use crate::parser::{event::{self, FileEvents}, javascript::JavaScriptParser, r#trait::LanguageParser};
+
+// TypeScript is a superset of JavaScript, so the tree is walked by JavaScriptParser, which also
+// understands the type annotations, interfaces and type aliases the TypeScript grammar adds.
+#[derive(Default)]
+pub struct TypeScriptParser {
+    javascript: JavaScriptParser,
+}
+
+impl TypeScriptParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_snippets(mut self, enabled: bool) -> Self {
+        self.javascript.set_record_snippets(enabled);
+        self
+    }
+}
+
+impl LanguageParser for TypeScriptParser {
+    fn language(&self) -> Language {
+        language_typescript()
+    }
+
+    // `.tsx` needs its own grammar: `<T>x` is a JSX element there but a type assertion in `.ts`.
+    fn language_for(&self, file_path: &Path) -> Language {
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("tsx") => language_tsx(),
+            _ => language_typescript(),
+        }
+    }
+
+    fn language_name(&self) -> &'static str {
+        "typescript"
+    }
+
+    fn language_kind(&self) -> event::Language {
+        event::Language::TypeScript
+    }
+
+    fn file_extensions(&self) -> &[&'static str] {
+        &["ts", "tsx"]
+    }
+
+    fn set_record_snippets(&mut self, enabled: bool) {
+        self.javascript.set_record_snippets(enabled);
+    }
+
+    fn walk_tree(&self, node: &Node, source_code: &str, file_events: &mut FileEvents) -> Result<(), Box<dyn std::error::Error>> {
+        self.javascript.walk_tree(node, source_code, file_events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::event::ParseEvent;
+
+    fn parse(suffix: &str, source: &str) -> FileEvents {
+        let file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        TypeScriptParser::new().parse_file(source, file.path()).unwrap()
+    }
+
+    #[test]
+    fn tsx_files_use_the_tsx_grammar() {
+        let parser = TypeScriptParser::new();
+        assert_eq!(parser.language_for(Path::new("app.tsx")), language_tsx());
+        assert_eq!(parser.language_for(Path::new("app.ts")), language_typescript());
+
+        let file_events = parse(".tsx", "const App = () => <Button label=\"go\" />;\n");
+        let callees: Vec<&str> = file_events.function_calls()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionCall { callee, .. } => Some(callee.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(callees, vec!["Button"]);
+    }
+
+    #[test]
+    fn interfaces_type_aliases_and_enums_are_reported_as_classes() {
+        let file_events = parse(".ts", "interface Shape {\n  area: number;\n  name?: string;\n}\n\ntype Id = string | number;\n\nenum Color {\n  Red,\n  Green = 2,\n}\n");
+
+        let classes: Vec<(&str, Vec<&str>)> = file_events.classes()
+            .filter_map(|event| match event {
+                ParseEvent::ClassDefinition { name, fields, .. } => Some((name.as_str(), fields.iter().map(String::as_str).collect())),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(classes, vec![
+            ("Shape", vec!["area", "name"]),
+            ("Id", vec![]),
+            ("Color", vec!["Red", "Green"]),
+        ]);
+    }
+
+    #[test]
+    fn typed_parameters_and_return_type() {
+        let file_events = parse(".ts", "export function scale(value: number, factor?: number, ...rest: string[]): number {\n  return value;\n}\n");
+
+        let (parameters, return_type) = file_events.functions()
+            .find_map(|event| match event {
+                ParseEvent::FunctionDefinition { parameters_detailed, return_type, .. } => Some((
+                    parameters_detailed.iter().map(|parameter| format!("{}: {}", parameter.name, parameter.param_type.as_deref().unwrap_or("_"))).collect::<Vec<_>>(),
+                    return_type.clone(),
+                )),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(parameters, vec!["value: number", "factor: number", "rest: string[]"]);
+        assert_eq!(return_type.as_deref(), Some("number"));
+    }
+}
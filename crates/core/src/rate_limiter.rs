@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub struct RateLimiter {
+    max_per_window: usize,
+    window: Duration,
+    recent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    pub fn per_second(max_per_second: usize) -> Self {
+        Self {
+            max_per_window: max_per_second.max(1),
+            window: Duration::from_secs(1),
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some(oldest) = self.recent.front() {
+            if now.duration_since(*oldest) >= self.window {
+                self.recent.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        self.evict_expired(now);
+
+        if self.recent.len() < self.max_per_window {
+            self.recent.push_back(now);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn time_until_available(&mut self) -> Duration {
+        let now = Instant::now();
+        self.evict_expired(now);
+
+        match self.recent.front() {
+            Some(oldest) if self.recent.len() >= self.max_per_window => self.window - now.duration_since(*oldest),
+            _ => Duration::ZERO,
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use encoding_rs::{Encoding, UTF_8};
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tree_sitter::{InputEdit, Point, Tree};
+
+use crate::parser::event::FileEvents;
+use crate::parser::registry::LanguageParserRegistry;
+use crate::parser::r#trait::PreviousParse;
+
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+// The last tree parsed for a file, with the source it was parsed from so the next edit can be computed, and
+// the unprocessed events the next reparse splices into.
+pub struct CachedTree {
+    tree: Tree,
+    source: String,
+    events: FileEvents,
+}
+
+// Shared by every clone of a SourceReader; the parse pool never has two parses of one file in flight.
+pub type TreeCache = Arc<Mutex<HashMap<PathBuf, CachedTree>>>;
+
+// Everything needed to turn a path into FileEvents, kept together so parse workers can each hold a copy.
+#[derive(Clone)]
+pub struct SourceReader {
+    parser_registry: Arc<LanguageParserRegistry>,
+    pub default_encoding: &'static Encoding,
+    pub extension_encodings: HashMap<String, &'static Encoding>,
+    pub mmap_threshold: Option<u64>,
+    pub streaming_extensions: HashSet<String>,
+    pub max_lines: Option<usize>,
+    pub tree_cache: Option<TreeCache>,
+}
+
+impl SourceReader {
+    pub fn new(parser_registry: LanguageParserRegistry) -> Self {
+        Self {
+            parser_registry: Arc::new(parser_registry),
+            default_encoding: UTF_8,
+            extension_encodings: HashMap::new(),
+            mmap_threshold: None,
+            streaming_extensions: HashSet::new(),
+            max_lines: None,
+            tree_cache: None,
+        }
+    }
+
+    // None while any clone of this reader (e.g. a parse worker's) still shares the registry.
+    pub fn registry_mut(&mut self) -> Option<&mut LanguageParserRegistry> {
+        Arc::get_mut(&mut self.parser_registry)
+    }
+
+    // Counts newlines in fixed-size chunks and stops as soon as the limit is passed, so a huge
+    // generated file is never held in memory just to be rejected.
+    pub fn exceeds_line_limit(&self, path: &Path) -> Result<bool, std::io::Error> {
+        let Some(max_lines) = self.max_lines else {
+            return Ok(false);
+        };
+
+        let mut file = File::open(path)?;
+        let mut buffer = vec![0u8; STREAM_CHUNK_SIZE];
+        let mut lines: usize = 0;
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                return Ok(false);
+            }
+            lines += buffer[..read].iter().filter(|&&byte| byte == b'\n').count();
+            if lines > max_lines {
+                return Ok(true);
+            }
+        }
+    }
+
+    fn encoding_for(&self, path: &Path) -> &'static Encoding {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.extension_encodings.get(ext))
+            .copied()
+            .unwrap_or(self.default_encoding)
+    }
+
+    fn decode_source<'a>(&self, path: &Path, bytes: &'a [u8]) -> Result<Cow<'a, str>, Box<dyn std::error::Error>> {
+        let encoding = self.encoding_for(path);
+
+        let (content, _, had_errors) = encoding.decode(bytes);
+        if had_errors {
+            return Err(format!("{} is not valid {}", path.display(), encoding.name()).into());
+        }
+
+        Ok(content)
+    }
+
+    pub fn parse_source(&self, path: &Path) -> Result<Option<FileEvents>, Box<dyn std::error::Error>> {
+        let streamed = path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.streaming_extensions.contains(ext));
+
+        // Chunks are handed to tree-sitter as raw bytes, so only UTF-8 sources can skip decoding.
+        if streamed && self.encoding_for(path) == UTF_8 {
+            let file = File::open(path)?;
+            // Safety: see the mmap threshold path below.
+            let mapped = unsafe { Mmap::map(&file)? };
+            return self.parser_registry.parse_file_streamed(path, &mapped, STREAM_CHUNK_SIZE);
+        }
+
+        let file_size = std::fs::metadata(path)?.len();
+
+        match self.mmap_threshold {
+            Some(threshold) if file_size >= threshold => {
+                let file = File::open(path)?;
+                // Safety: the mapping only lives for this parse. A file truncated underneath us by
+                // another process can still fault, which is the accepted cost of the opt-in mmap path.
+                let mapped = unsafe { Mmap::map(&file)? };
+                let content = self.decode_source(path, &mapped)?;
+                self.parse_content(path, &content)
+            }
+            _ => {
+                let bytes = std::fs::read(path)?;
+                let content = self.decode_source(path, &bytes)?;
+                self.parse_content(path, &content)
+            }
+        }
+    }
+
+    fn parse_content(&self, path: &Path, content: &str) -> Result<Option<FileEvents>, Box<dyn std::error::Error>> {
+        let Some(tree_cache) = &self.tree_cache else {
+            return self.parser_registry.parse_file(path, content);
+        };
+
+        // Taken out of the cache so the lock isn't held while parsing; a failed parse leaves no stale tree behind.
+        let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let cached = tree_cache.lock().ok().and_then(|mut cache| cache.remove(&key));
+        let previous = cached.map(|CachedTree { mut tree, source, events }| {
+            let edit = input_edit(&source, content);
+            if let Some(edit) = &edit {
+                tree.edit(edit);
+            }
+            (tree, edit, events)
+        });
+        let previous = previous.as_ref().map(|(tree, edit, events)| PreviousParse { tree, edit: *edit, events });
+
+        let Some((file_events, tree)) = self.parser_registry.parse_file_incremental(path, content, previous)? else {
+            return Ok(None);
+        };
+
+        let mut processed = file_events.clone();
+        self.parser_registry.post_process(&mut processed);
+        if let (Some(tree), Ok(mut cache)) = (tree, tree_cache.lock()) {
+            cache.insert(key, CachedTree { tree, source: content.to_string(), events: file_events });
+        }
+        Ok(Some(processed))
+    }
+
+    pub fn forget_tree(&self, path: &Path) {
+        if let Some(Ok(mut cache)) = self.tree_cache.as_ref().map(|cache| cache.lock()) {
+            cache.remove(path);
+        }
+    }
+
+    pub fn move_tree(&self, from: &Path, to: &Path) {
+        if let Some(Ok(mut cache)) = self.tree_cache.as_ref().map(|cache| cache.lock())
+            && let Some(mut cached) = cache.remove(from) {
+            cached.events.file_path = to.to_path_buf();
+            cache.insert(to.to_path_buf(), cached);
+        }
+    }
+
+    pub fn clear_trees(&self) {
+        if let Some(Ok(mut cache)) = self.tree_cache.as_ref().map(|cache| cache.lock()) {
+            cache.clear();
+        }
+    }
+}
+
+
+// Describes the change from `old` to `new` as one edit spanning everything between their common
+// prefix and common suffix. None when nothing changed.
+fn input_edit(old: &str, new: &str) -> Option<InputEdit> {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+    let prefix = old_bytes.iter().zip(new_bytes).take_while(|(a, b)| a == b).count();
+    if prefix == old_bytes.len() && prefix == new_bytes.len() {
+        return None;
+    }
+
+    let suffix = old_bytes[prefix..].iter().rev()
+        .zip(new_bytes[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (old_end_byte, new_end_byte) = (old_bytes.len() - suffix, new_bytes.len() - suffix);
+    Some(InputEdit {
+        start_byte: prefix,
+        old_end_byte,
+        new_end_byte,
+        start_position: byte_position(old_bytes, prefix),
+        old_end_position: byte_position(old_bytes, old_end_byte),
+        new_end_position: byte_position(new_bytes, new_end_byte),
+    })
+}
+
+// tree-sitter columns count bytes, not characters.
+fn byte_position(source: &[u8], offset: usize) -> Point {
+    let before = &source[..offset];
+    let row = before.iter().filter(|&&byte| byte == b'\n').count();
+    let column = before.iter().rposition(|&byte| byte == b'\n').map_or(offset, |newline| offset - newline - 1);
+    Point { row, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::event::ParseEvent;
+
+    fn function_names(file_events: &FileEvents) -> Vec<&str> {
+        file_events.functions()
+            .filter_map(|event| match event {
+                ParseEvent::FunctionDefinition { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn latin1_source_decodes_without_mojibake() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("menu.py");
+        // "def café():" with é as the single Latin-1 byte 0xE9, which is invalid UTF-8.
+        std::fs::write(&path, b"def caf\xe9():\n    return 'cr\xe8me'\n").unwrap();
+
+        let mut reader = SourceReader::new(LanguageParserRegistry::new());
+        reader.extension_encodings.insert("py".to_string(), encoding_rs::WINDOWS_1252);
+        let file_events = reader.parse_source(&path).unwrap().unwrap();
+
+        assert_eq!(function_names(&file_events), vec!["café"]);
+    }
+
+    #[test]
+    fn undecodable_source_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("menu.py");
+        std::fs::write(&path, b"def caf\xe9():\n    pass\n").unwrap();
+
+        let reader = SourceReader::new(LanguageParserRegistry::new());
+
+        assert!(reader.parse_source(&path).is_err());
+    }
+
+    fn large_python_file(dir: &Path, functions: usize) -> PathBuf {
+        let path = dir.join("large.py");
+        let source: String = (0..functions)
+            .map(|i| format!("def function_{i}(a, b):\n    return a + b + {i}\n\n"))
+            .collect();
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn mmap_path_matches_heap_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = large_python_file(dir.path(), 2_000);
+
+        let heap_reader = SourceReader::new(LanguageParserRegistry::new());
+        let mut mmap_reader = SourceReader::new(LanguageParserRegistry::new());
+        mmap_reader.mmap_threshold = Some(1024);
+
+        let from_heap = heap_reader.parse_source(&path).unwrap().unwrap();
+        let from_mmap = mmap_reader.parse_source(&path).unwrap().unwrap();
+
+        assert_eq!(from_mmap.functions().count(), 2_000);
+        assert_eq!(format!("{:?}", from_mmap.events), format!("{:?}", from_heap.events));
+    }
+
+    #[test]
+    fn streamed_parse_matches_in_memory_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = large_python_file(dir.path(), 5_000);
+        assert!(std::fs::metadata(&path).unwrap().len() > 2 * STREAM_CHUNK_SIZE as u64);
+
+        let in_memory = SourceReader::new(LanguageParserRegistry::new());
+        let mut streaming = SourceReader::new(LanguageParserRegistry::new());
+        streaming.streaming_extensions.insert("py".to_string());
+
+        let from_memory = in_memory.parse_source(&path).unwrap().unwrap();
+        let streamed = streaming.parse_source(&path).unwrap().unwrap();
+
+        assert_eq!(streamed.functions().count(), 5_000);
+        assert_eq!(format!("{:?}", streamed.events), format!("{:?}", from_memory.events));
+    }
+
+    #[test]
+    fn registry_is_only_mutable_while_unshared() {
+        let mut reader = SourceReader::new(LanguageParserRegistry::new());
+        let worker_copy = reader.clone();
+
+        assert!(reader.registry_mut().is_none());
+
+        drop(worker_copy);
+        assert!(reader.registry_mut().is_some());
+    }
+
+    fn incremental_reader() -> SourceReader {
+        let mut reader = SourceReader::new(LanguageParserRegistry::new());
+        reader.tree_cache = Some(TreeCache::default());
+        reader
+    }
+
+    fn events_outside(file_events: &FileEvents, lines: std::ops::RangeInclusive<usize>) -> Vec<String> {
+        let inside: Vec<String> = file_events.events_in_range(*lines.start(), *lines.end()).map(|event| format!("{:?}", event)).collect();
+        file_events.events.iter()
+            .map(|event| format!("{:?}", event))
+            .filter(|event| !inside.contains(event))
+            .collect()
+    }
+
+    #[test]
+    fn incremental_reparse_rewalks_only_the_edited_function() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = large_python_file(dir.path(), 200);
+        let reader = incremental_reader();
+        let before = reader.parse_source(&path).unwrap().unwrap();
+
+        // Renaming a cached event the edit doesn't reach shows it was carried over rather than walked again.
+        let key = path.canonicalize().unwrap();
+        if let Some(cached) = reader.tree_cache.as_ref().unwrap().lock().unwrap().get_mut(&key) {
+            for event in &mut cached.events.events {
+                if let ParseEvent::FunctionDefinition { name, .. } = event && name == "function_150" {
+                    *name = "carried_over".to_string();
+                }
+            }
+        }
+
+        // function_100 spans lines 301-302; the edit keeps the line count.
+        let source = std::fs::read_to_string(&path).unwrap().replace("    return a + b + 100\n", "    return combine(a, b)\n");
+        std::fs::write(&path, source).unwrap();
+        let after = reader.parse_source(&path).unwrap().unwrap();
+
+        assert!(function_names(&after).contains(&"carried_over"));
+        assert!(after.function_calls().any(|event| matches!(event, ParseEvent::FunctionCall { callee, line: 302, .. } if callee == "combine")));
+        let unchanged: Vec<String> = events_outside(&before, 301..=302).into_iter()
+            .map(|event| event.replace("\"function_150\"", "\"carried_over\""))
+            .collect();
+        assert_eq!(events_outside(&after, 301..=302), unchanged);
+    }
+
+    #[test]
+    fn incremental_splice_matches_a_full_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = large_python_file(dir.path(), 50);
+        let reader = incremental_reader();
+        reader.parse_source(&path).unwrap();
+
+        let edits = [
+            // Grows one function by two lines, moving everything below it.
+            ("    return a + b + 20\n", "    total = a + b\n    log(total)\n    return total + 20\n"),
+            // Removes a whole function.
+            ("def function_30(a, b):\n    return a + b + 30\n\n", ""),
+            // Reaches a module-level statement, so the whole tree is walked.
+            ("def function_40(a, b):\n", "x = 1\ndef function_40(a, b):\n"),
+        ];
+        for (old, new) in edits {
+            let source = std::fs::read_to_string(&path).unwrap().replace(old, new);
+            std::fs::write(&path, source).unwrap();
+
+            let spliced = reader.parse_source(&path).unwrap().unwrap();
+            let full = SourceReader::new(LanguageParserRegistry::new()).parse_source(&path).unwrap().unwrap();
+
+            assert_eq!(format!("{:?}", spliced.events), format!("{:?}", full.events));
+            assert_eq!(spliced.line_count, full.line_count);
+        }
+    }
+}
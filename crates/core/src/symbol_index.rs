@@ -0,0 +1,247 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::parser::event::{FileEvents, ParseEvent};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SymbolLocation {
+    pub file_path: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    definitions: HashMap<String, Vec<SymbolLocation>>,
+    references: HashMap<String, Vec<SymbolLocation>>,
+    file_symbols: HashMap<PathBuf, HashSet<String>>,
+    file_imports: HashMap<PathBuf, Vec<String>>,
+    type_usages: HashMap<String, Vec<(PathBuf, String)>>,
+}
+
+impl SymbolIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_files<'a, I: IntoIterator<Item = &'a FileEvents>>(files: I) -> Self {
+        let mut index = Self::new();
+        for file_events in files {
+            index.add_file(file_events);
+        }
+        index
+    }
+
+    pub fn add_file(&mut self, file_events: &FileEvents) {
+        let path = &file_events.file_path;
+        self.remove_file(path);
+
+        let mut symbols: HashSet<String> = HashSet::new();
+        let mut imports: Vec<String> = Vec::new();
+
+        for event in &file_events.events {
+            let (map, name, line) = match event {
+                ParseEvent::ImportStatement { module, .. } => {
+                    imports.push(module.clone());
+                    continue;
+                }
+                ParseEvent::FunctionDefinition { name, start_line, parameters_detailed, return_type, .. } => {
+                    let annotations = parameters_detailed.iter()
+                        .filter_map(|parameter| parameter.param_type.as_deref())
+                        .chain(return_type.as_deref());
+
+                    let mut type_names: HashSet<&str> = HashSet::new();
+                    for annotation in annotations {
+                        type_names.extend(annotation.split(|c: char| !(c.is_alphanumeric() || c == '_')).filter(|part| !part.is_empty()));
+                    }
+                    for type_name in type_names {
+                        self.type_usages.entry(type_name.to_string()).or_default().push((path.clone(), name.clone()));
+                    }
+
+                    (&mut self.definitions, name, *start_line)
+                }
+                ParseEvent::ClassDefinition { name, start_line, .. }
+                | ParseEvent::MacroDefinition { name, start_line, .. } => (&mut self.definitions, name, *start_line),
+                ParseEvent::VariableDefinition { name, line, .. } => (&mut self.definitions, name, *line),
+                ParseEvent::FunctionCall { callee, line, .. } => (&mut self.references, callee, *line),
+                ParseEvent::VariableAccess { variable, line, .. } => (&mut self.references, variable, *line),
+                _ => continue,
+            };
+
+            map.entry(name.clone()).or_default().push(SymbolLocation { file_path: path.clone(), line });
+            symbols.insert(name.clone());
+        }
+
+        self.file_symbols.insert(path.clone(), symbols);
+        self.file_imports.insert(path.clone(), imports);
+    }
+
+    pub fn remove_file(&mut self, path: &Path) {
+        self.file_imports.remove(path);
+        self.type_usages.retain(|_, usages| {
+            usages.retain(|(file_path, _)| file_path != path);
+            !usages.is_empty()
+        });
+
+        let Some(symbols) = self.file_symbols.remove(path) else {
+            return;
+        };
+
+        for name in symbols {
+            for map in [&mut self.definitions, &mut self.references] {
+                if let Some(locations) = map.get_mut(&name) {
+                    locations.retain(|location| location.file_path != path);
+                    if locations.is_empty() {
+                        map.remove(&name);
+                    }
+                }
+            }
+        }
+    }
+
+    // A file present in both indexes keeps `other`'s entries, the same replace semantics as add_file.
+    pub fn merge(&mut self, other: SymbolIndex) {
+        for path in other.file_symbols.keys() {
+            self.remove_file(path);
+        }
+
+        self.file_symbols.extend(other.file_symbols);
+        self.file_imports.extend(other.file_imports);
+
+        for (type_name, usages) in other.type_usages {
+            let existing = self.type_usages.entry(type_name).or_default();
+            for usage in usages {
+                if !existing.contains(&usage) {
+                    existing.push(usage);
+                }
+            }
+        }
+
+        for (map, other_map) in [(&mut self.definitions, other.definitions), (&mut self.references, other.references)] {
+            for (name, locations) in other_map {
+                let existing = map.entry(name).or_default();
+                for location in locations {
+                    if !existing.contains(&location) {
+                        existing.push(location);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn find_definition(&self, name: &str) -> &[SymbolLocation] {
+        self.definitions.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn references_to(&self, name: &str) -> &[SymbolLocation] {
+        self.references.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Matches whole identifiers inside annotations, so `Optional[Config]` counts as a use of `Config`.
+    pub fn functions_using_type(&self, type_name: &str) -> Vec<(PathBuf, String)> {
+        let mut functions: Vec<(PathBuf, String)> = self.type_usages.get(type_name).cloned().unwrap_or_default();
+        functions.sort();
+        functions
+    }
+
+    pub fn importers_of(&self, module: &str) -> Vec<PathBuf> {
+        let submodule_prefix = format!("{}.", module);
+
+        let mut importers: Vec<PathBuf> = self.file_imports.iter()
+            .filter(|(_, imports)| imports.iter().any(|imported| imported == module || imported.starts_with(&submodule_prefix)))
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        importers.sort();
+        importers
+    }
+
+    pub fn hotspots(&self, n: usize) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self.references.iter()
+            .map(|(name, locations)| (name.clone(), locations.len()))
+            .collect();
+
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(n);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::registry::LanguageParserRegistry;
+
+    fn parse_files(dir: &Path, files: &[(&str, &str)]) -> Vec<FileEvents> {
+        let registry = LanguageParserRegistry::new();
+        files.iter()
+            .map(|(name, source)| {
+                let path = dir.join(name);
+                std::fs::write(&path, source).unwrap();
+                registry.parse_file(&path, source).unwrap().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hotspots_are_ordered_by_reference_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = parse_files(dir.path(), &[(
+            "app.py",
+            "def busy():\n    pass\n\ndef quiet():\n    pass\n\nbusy()\nbusy()\nquiet()\nbusy()\n",
+        )]);
+
+        let index = SymbolIndex::from_files(&files);
+
+        assert_eq!(index.hotspots(10), vec![("busy".to_string(), 3), ("quiet".to_string(), 1)]);
+        assert_eq!(index.hotspots(1), vec![("busy".to_string(), 3)]);
+    }
+
+    #[test]
+    fn importers_of_returns_exactly_the_importing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = parse_files(dir.path(), &[
+            ("a.py", "import utils\n"),
+            ("b.py", "from utils import helper\n"),
+            ("c.py", "import other\n"),
+        ]);
+
+        let index = SymbolIndex::from_files(&files);
+
+        assert_eq!(index.importers_of("utils"), vec![dir.path().join("a.py"), dir.path().join("b.py")]);
+        assert_eq!(index.importers_of("other"), vec![dir.path().join("c.py")]);
+    }
+
+    #[test]
+    fn merged_shards_answer_for_both() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = parse_files(dir.path(), &[("a.py", "def alpha():\n    pass\n"), ("b.py", "def beta():\n    pass\n\nalpha()\n")]);
+        let mut index = SymbolIndex::from_files(&files[..1]);
+        let shard = SymbolIndex::from_files(&files[1..]);
+
+        index.merge(shard);
+
+        assert_eq!(index.find_definition("alpha"), [SymbolLocation { file_path: dir.path().join("a.py"), line: 1 }]);
+        assert_eq!(index.find_definition("beta"), [SymbolLocation { file_path: dir.path().join("b.py"), line: 1 }]);
+
+        // Merging a shard for a file that's already indexed replaces its entries instead of duplicating them.
+        index.merge(SymbolIndex::from_files(&files[..1]));
+        assert_eq!(index.find_definition("alpha").len(), 1);
+    }
+
+    #[test]
+    fn functions_using_type_finds_parameters_and_returns() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = parse_files(dir.path(), &[
+            ("app.py", "def run(config: Config):\n    pass\n\ndef unrelated(x: int):\n    pass\n"),
+            ("load.py", "def load(path: str) -> Config:\n    pass\n"),
+        ]);
+
+        let index = SymbolIndex::from_files(&files);
+
+        assert_eq!(index.functions_using_type("Config"), vec![
+            (dir.path().join("app.py"), "run".to_string()),
+            (dir.path().join("load.py"), "load".to_string()),
+        ]);
+        assert!(index.functions_using_type("Missing").is_empty());
+    }
+}